@@ -5,17 +5,17 @@ use std::iter::Peekable;
 use logos::{Logos, SpannedIter};
 use serde::{Deserialize, Serialize};
 
-/// TODO:
-/// instead of storing the line number and column, we can store the start/end
-/// position only and then calculate the line/column when needed.  You'll need
-/// the original input string to do this.
-///
-/// We don't actually need the line number and column info of each node, we only
-/// need to calculate it when reporting an error for those couple of positions
-/// we care about.
+// TODO:
+// instead of storing the line number and column, we can store the start/end
+// position only and then calculate the line/column when needed.  You'll need
+// the original input string to do this.
+//
+// We don't actually need the line number and column info of each node, we only
+// need to calculate it when reporting an error for those couple of positions
+// we care about.
 
 /// Report locations in the source code
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SourceLoc {
     pub line: usize,
 
@@ -27,10 +27,36 @@ pub struct SourceLoc {
 pub enum TokenKind {
     #[token("=")]
     Equals,
+    #[token("=>")]
+    FatArrow,
+    #[token("->")]
+    Arrow,
+    #[token("+=")]
+    AddEquals,
+    #[token("-=")]
+    SubtractEquals,
+    #[token("*=")]
+    MultiplyEquals,
+    #[token("/=")]
+    DivideEquals,
+    #[token("%=")]
+    ModulusEquals,
+    #[token("&=")]
+    AmpersandEquals,
+    #[token("|=")]
+    PipeEquals,
+    #[token("^=")]
+    CaretEquals,
+    #[token("<<=")]
+    ShiftLeftEquals,
+    #[token(">>=")]
+    ShiftRightEquals,
     #[token(",")]
     Comma,
     #[token(";")]
     Semicolon,
+    #[token(":")]
+    Colon,
 
     #[token("+")]
     Add,
@@ -42,6 +68,28 @@ pub enum TokenKind {
     Divide,
     #[token("%")]
     Modulus,
+    #[token("++")]
+    Increment,
+    #[token("--")]
+    Decrement,
+    #[token("&")]
+    Ampersand,
+    #[token("|")]
+    Pipe,
+    #[token("^")]
+    Caret,
+    #[token("~")]
+    Tilde,
+    #[token("!")]
+    Bang,
+    #[token("<<")]
+    ShiftLeft,
+    #[token(">>")]
+    ShiftRight,
+    #[token("&&")]
+    AndAnd,
+    #[token("||")]
+    OrOr,
     #[token("==")]
     EqualsEquals,
     #[token("!=")]
@@ -54,6 +102,18 @@ pub enum TokenKind {
     LessEqual,
     #[token(">=")]
     GreaterEq,
+    #[token("?")]
+    Question,
+    #[token("?.")]
+    QuestionDot,
+    #[token("??")]
+    QuestionQuestion,
+    #[token(".")]
+    Dot,
+    #[token("..")]
+    DotDot,
+    #[token("..=")]
+    DotDotEquals,
 
     #[token("(")]
     OpenParen,
@@ -70,16 +130,44 @@ pub enum TokenKind {
 
     #[token("use")]
     UseKw,
+    #[token("as")]
+    AsKw,
     #[token("true")]
     TrueKw,
     #[token("false")]
     FalseKw,
+    #[token("null")]
+    NullKw,
     #[token("pub")]
     PubKw,
+    #[token("private")]
+    PrivateKw,
+    #[token("protected")]
+    ProtectedKw,
+    #[token("internal")]
+    InternalKw,
+    #[token("static")]
+    StaticKw,
+    #[token("this")]
+    ThisKw,
+    #[token("let")]
+    LetKw,
+    #[token("var")]
+    VarKw,
+    #[token("const")]
+    ConstKw,
     #[token("namespace")]
     NamespaceKw,
+    #[token("fn")]
+    FnKw,
     #[token("class")]
     ClassKw,
+    #[token("interface")]
+    InterfaceKw,
+    #[token("enum")]
+    EnumKw,
+    #[token("struct")]
+    StructKw,
     #[token("if")]
     IfKw,
     #[token("elif")]
@@ -88,17 +176,53 @@ pub enum TokenKind {
     ElseKw,
     #[token("while")]
     WhileKw,
+    #[token("return")]
+    ReturnKw,
+    #[token("for")]
+    ForKw,
+    #[token("do")]
+    DoKw,
+    #[token("loop")]
+    LoopKw,
+    #[token("in")]
+    InKw,
+    #[token("break")]
+    BreakKw,
+    #[token("continue")]
+    ContinueKw,
+    #[token("new")]
+    NewKw,
+    #[token("match")]
+    MatchKw,
+    #[token("throw")]
+    ThrowKw,
+    #[token("try")]
+    TryKw,
+    #[token("catch")]
+    CatchKw,
+    #[token("finally")]
+    FinallyKw,
+    #[token("defer")]
+    DeferKw,
 
 
     // Literals
-    #[regex(r"[_a-zA-Z][_.0-9a-zA-Z]*")]
+    // Dotted paths like `core.io.writeLn` are a single identifier, `a..b` is a range
+    #[regex(r"[_a-zA-Z][_0-9a-zA-Z]*(\.[_a-zA-Z][_0-9a-zA-Z]*)*")]
     Identifier,
-    #[regex(r"[-]?[0-9][0-9]*")]
+    // Numeric literals may contain `_` separators and end in a width suffix
+    #[regex(r"[0-9][0-9_]*([iu](8|16|32|64))?")]
     IntLit,
-    #[regex(r"[-]?[0-9]+\.[0-9]+")]
+    #[regex(r"[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?(f32|f64)?")]
+    #[regex(r"[0-9][0-9_]*[eE][+-]?[0-9][0-9_]*(f32|f64)?")]
+    #[regex(r"[0-9][0-9_]*(f32|f64)")]
     FloatLit,
-    #[regex(r"0[xX][0-9a-fA-F]+")]
+    #[regex(r"0[xX]_*[0-9a-fA-F][0-9a-fA-F_]*([iu](8|16|32|64))?")]
     HexIntLit,
+    #[regex(r"0[bB]_*[01][01_]*([iu](8|16|32|64))?")]
+    BinIntLit,
+    #[regex(r"0[oO]_*[0-7][0-7_]*([iu](8|16|32|64))?")]
+    OctIntLit,
     #[regex(r#""(\\[\\"]|[^"])*""#)]
     StringLit,
 
@@ -124,19 +248,116 @@ pub enum Op {
     Mod,
     And,
     Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Eq,
     BangEq,
     Less,
     LessEq,
     Greater,
     GreaterEq,
+    NullCoalesce,
+    Range,
+    RangeInclusive,
 
     // Unary operators
     Neg,
     Bang,
+    BitNot,
+    Increment,
+    Decrement,
 }
 
 impl Op {
+    /// Map a compound assignment token (`+=`, `<<=`, ...) onto its operator
+    pub fn from_assignment_token(kind: &TokenKind) -> Option<Op> {
+        match kind {
+            TokenKind::AddEquals => Some(Op::Add),
+            TokenKind::SubtractEquals => Some(Op::Sub),
+            TokenKind::MultiplyEquals => Some(Op::Mul),
+            TokenKind::DivideEquals => Some(Op::Div),
+            TokenKind::ModulusEquals => Some(Op::Mod),
+            TokenKind::AmpersandEquals => Some(Op::BitAnd),
+            TokenKind::PipeEquals => Some(Op::BitOr),
+            TokenKind::CaretEquals => Some(Op::BitXor),
+            TokenKind::ShiftLeftEquals => Some(Op::ShiftLeft),
+            TokenKind::ShiftRightEquals => Some(Op::ShiftRight),
+            _ => None,
+        }
+    }
+
+    /// Map a token onto the binary operator it represents, if any
+    pub fn from_binary_token(kind: &TokenKind) -> Option<Op> {
+        match kind {
+            TokenKind::Add => Some(Op::Add),
+            TokenKind::Subtract => Some(Op::Sub),
+            TokenKind::Multiply => Some(Op::Mul),
+            TokenKind::Divide => Some(Op::Div),
+            TokenKind::Modulus => Some(Op::Mod),
+            TokenKind::AndAnd => Some(Op::And),
+            TokenKind::OrOr => Some(Op::Or),
+            TokenKind::Ampersand => Some(Op::BitAnd),
+            TokenKind::Pipe => Some(Op::BitOr),
+            TokenKind::Caret => Some(Op::BitXor),
+            TokenKind::ShiftLeft => Some(Op::ShiftLeft),
+            TokenKind::ShiftRight => Some(Op::ShiftRight),
+            TokenKind::EqualsEquals => Some(Op::Eq),
+            TokenKind::NotEqual => Some(Op::BangEq),
+            TokenKind::LessThan => Some(Op::Less),
+            TokenKind::GreaterThan => Some(Op::Greater),
+            TokenKind::LessEqual => Some(Op::LessEq),
+            TokenKind::GreaterEq => Some(Op::GreaterEq),
+            TokenKind::QuestionQuestion => Some(Op::NullCoalesce),
+            TokenKind::DotDot => Some(Op::Range),
+            TokenKind::DotDotEquals => Some(Op::RangeInclusive),
+            _ => None,
+        }
+    }
+
+    /// Map a token onto the prefix operator it represents, if any
+    pub fn from_unary_token(kind: &TokenKind) -> Option<Op> {
+        match kind {
+            TokenKind::Subtract => Some(Op::Neg),
+            TokenKind::Bang => Some(Op::Bang),
+            TokenKind::Tilde => Some(Op::BitNot),
+            TokenKind::Increment => Some(Op::Increment),
+            TokenKind::Decrement => Some(Op::Decrement),
+            _ => None,
+        }
+    }
+
+    /// Binding power of a binary operator, higher binds tighter
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Op::NullCoalesce => 1,
+            Op::Or => 2,
+            Op::And => 3,
+            Op::BitOr => 4,
+            Op::BitXor => 5,
+            Op::BitAnd => 6,
+            Op::Eq | Op::BangEq => 7,
+            Op::Less | Op::LessEq | Op::Greater | Op::GreaterEq => 8,
+            Op::Range | Op::RangeInclusive => 9,
+            Op::ShiftLeft | Op::ShiftRight => 10,
+            Op::Add | Op::Sub => 11,
+            Op::Mul | Op::Div | Op::Mod => 12,
+
+            Op::Neg | Op::Bang | Op::BitNot | Op::Increment | Op::Decrement => 13,
+        }
+    }
+
+    /// `a ?? b ?? c` groups as `a ?? (b ?? c)`, everything else groups to the left
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Op::NullCoalesce)
+    }
+
+    pub fn is_range(&self) -> bool {
+        matches!(self, Op::Range | Op::RangeInclusive)
+    }
+
     pub fn to_str(&self) -> &str {
         match self {
             Op::Add => "+",
@@ -146,15 +367,26 @@ impl Op {
             Op::Mod => "%",
             Op::And => "&&",
             Op::Or => "||",
+            Op::BitAnd => "&",
+            Op::BitOr => "|",
+            Op::BitXor => "^",
+            Op::ShiftLeft => "<<",
+            Op::ShiftRight => ">>",
             Op::Eq => "==",
             Op::BangEq => "!=",
             Op::Less => "<",
             Op::LessEq => "<=",
             Op::Greater => ">",
             Op::GreaterEq => ">=",
+            Op::NullCoalesce => "??",
+            Op::Range => "..",
+            Op::RangeInclusive => "..=",
 
             Op::Neg => "-",
             Op::Bang => "!",
+            Op::BitNot => "~",
+            Op::Increment => "++",
+            Op::Decrement => "--",
         }
     }
 }
@@ -163,19 +395,61 @@ impl Op {
 pub struct Token<'a> {
     pub kind: TokenKind,
     pub location: SourceLoc,
+    pub span: Span,
     pub literal: &'a str,
 }
 
 impl Token<'_> {
     pub fn is_int_literal(&self) -> bool {
-        matches!(self.kind, TokenKind::IntLit | TokenKind::HexIntLit)
+        matches!(
+            self.kind,
+            TokenKind::IntLit | TokenKind::HexIntLit | TokenKind::BinIntLit | TokenKind::OctIntLit
+        )
     }
 
-    pub fn to_int_literal(&self) -> i32 {
-        match self.kind {
-            TokenKind::IntLit => self.literal.parse().unwrap(),
-            TokenKind::HexIntLit => i32::from_str_radix(&self.literal[2..], 16).unwrap(),
+    /// Split a numeric literal into its digits and its width suffix (`u8`, `f32`, ...)
+    fn split_numeric_suffix(&self) -> (&str, Option<&str>) {
+        let suffix_start = match self.kind {
+            TokenKind::FloatLit => self.literal.find('f'),
+            _ => self.literal.find(['i', 'u']),
+        };
+
+        match suffix_start {
+            Some(index) => (&self.literal[..index], Some(&self.literal[index..])),
+            None => (self.literal, None),
+        }
+    }
+
+    pub fn numeric_suffix(&self) -> Option<&str> {
+        self.split_numeric_suffix().1
+    }
+
+    /// Parse the value of an integer literal, ignoring `_` separators and any width suffix.
+    /// Returns `None` if the value doesn't fit in an `i128`.
+    pub fn to_int_literal(&self) -> Option<i128> {
+        let (literal, _) = self.split_numeric_suffix();
+
+        let (radix, digits) = match self.kind {
+            TokenKind::IntLit => (10, literal),
+            TokenKind::HexIntLit => (16, &literal[2..]),
+            TokenKind::BinIntLit => (2, &literal[2..]),
+            TokenKind::OctIntLit => (8, &literal[2..]),
             _ => panic!("Not an integer literal"),
+        };
+
+        let digits: String = digits.chars().filter(|c| *c != '_').collect();
+        i128::from_str_radix(&digits, radix).ok()
+    }
+
+    /// Parse the value of a float literal, ignoring `_` separators and any width suffix.
+    pub fn to_float_literal(&self) -> f64 {
+        match self.kind {
+            TokenKind::FloatLit => {
+                let (literal, _) = self.split_numeric_suffix();
+                let digits: String = literal.chars().filter(|c| *c != '_').collect();
+                digits.parse().unwrap()
+            }
+            _ => panic!("Not a float literal"),
         }
     }
 }
@@ -189,6 +463,13 @@ pub struct TokenIter<'a> {
     src: &'a str,
 }
 
+/// Byte offsets of a piece of the source code, `end` is exclusive
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 impl fmt::Display for SourceLoc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(L{}, C{}:{})", self.line, self.start, self.end)
@@ -233,14 +514,14 @@ impl<'a> Iterator for TokenIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner
             .next()
-            .map(|(kind, span)| {
+            .and_then(|(kind, span)| {
                 Some(Token {
                     kind: kind.ok()?,
                     location: self.get_location(),
+                    span: Span { start: span.start, end: span.end },
                     literal: &self.src[span],
                 })
             })
-            .flatten()
     }
 }
 
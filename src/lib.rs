@@ -9,7 +9,8 @@ pub mod node;
 pub fn parse(code: &str) -> Program {
     let tokens: LexerIter = lexer::lex_tokens(code);  // Lex tokens from the source code
 
-    let tokvec: Vec<_> = tokens.collect(); // Collect tokens into a Vec
+    // Comments are kept by the lexer but have no meaning to the parser
+    let tokvec: Vec<_> = tokens.filter(|token| token.kind != lexer::TokenKind::Comment).collect(); // Collect tokens into a Vec
     let mut parser = Parser::new(&tokvec); // Pass the slice of tokens to the parser
     
     let program = parser.parse();  // Parse the tokens (assuming `parse` is implemented)
@@ -44,4 +45,1427 @@ mod tests {
 
         println!("{:#?}", program);
     }
-}
\ No newline at end of file
+
+    fn function_statements(program: &Program) -> &Vec<node::Statement> {
+        match &program.namespaces[0].definitions[0] {
+            node::Definition::FunctionDefinition(function) => &function.body.statements,
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+    }
+
+    fn variable_value(statement: &node::Statement) -> &node::Expression {
+        match statement {
+            node::Statement::VariableDefinition(definition) => &definition.value,
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+    }
+
+    fn type_name(dtype: &Option<node::TypeExpr>) -> Option<String> {
+        return dtype.as_ref().map(|dtype| dtype.to_string());
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    int a = 0b1010;
+                    int b = 0o17;
+                    int c = 0xFF;
+                    int d = 1_000_000;
+                    float e = 1.5e-3;
+                    u8 f = 10u8;
+                    float g = 2.0f32;
+                    u8 h = 256u8;
+                    int i = 99999999999999999999;
+                    i8 j = -128i8;
+                    i8 k = -129i8;
+                    int l = a-1;
+                    int m = arr[i-1];
+                    float n = -2.5;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        let ints: Vec<_> = [0, 1, 2, 3, 5, 7, 8, 9, 10].iter().map(|i| match variable_value(&statements[*i]) {
+            node::Expression::Term(node::Term::IntegerLiteral(literal)) => (literal.value, literal.suffix),
+            value => panic!("Expected an integer literal, found {:?}", value),
+        }).collect();
+        assert_eq!(ints, vec![
+            (10, None),
+            (15, None),
+            (255, None),
+            (1_000_000, None),
+            (10, Some(node::NumericSuffix::U8)),
+            (0, Some(node::NumericSuffix::U8)),
+            (0, None),
+            (-128, Some(node::NumericSuffix::I8)),
+            (0, Some(node::NumericSuffix::I8)),
+        ]);
+
+        // A `-` right after a name is subtraction, not part of the literal
+        assert!(matches!(variable_value(&statements[11]), node::Expression::BinaryOperation(operation) if operation.op == "-"));
+        match variable_value(&statements[12]) {
+            node::Expression::Index(index) => assert!(matches!(*index.index, node::Expression::BinaryOperation(ref operation) if operation.op == "-")),
+            value => panic!("Expected an index, found {:?}", value),
+        }
+        assert!(matches!(variable_value(&statements[13]), node::Expression::Term(node::Term::FloatLiteral(literal)) if literal.value == -2.5));
+
+        match variable_value(&statements[4]) {
+            node::Expression::Term(node::Term::FloatLiteral(literal)) => assert_eq!(literal.value, 1.5e-3),
+            value => panic!("Expected a float literal, found {:?}", value),
+        }
+        match variable_value(&statements[6]) {
+            node::Expression::Term(node::Term::FloatLiteral(literal)) => assert_eq!(literal.suffix, Some(node::NumericSuffix::F32)),
+            value => panic!("Expected a float literal, found {:?}", value),
+        }
+
+        // The three out of range literals are reported instead of panicking
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Integer literal `256u8` does not fit in u8",
+            "Integer literal `99999999999999999999` does not fit in i64",
+            "Integer literal `-129i8` does not fit in i8",
+        ]);
+
+        // Prefixes need at least one digit
+        assert!(lexer::lex_tokens("0x_ 0b_ 0o_").all(|token| ! token.is_int_literal() || token.literal == "0"));
+    }
+
+    #[test]
+    fn test_null_and_nullable_types() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    Foo? a = null;
+                    string b = a?.name ?? "none";
+                    int c = a?.size() ?? 0 ?? 1;
+                    bool d = 1 + 2 * 3 == 7;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::VariableDefinition(definition) => {
+                assert!(matches!(definition.dtype, Some(node::TypeExpr::Nullable(_))));
+                assert!(matches!(definition.value, node::Expression::Term(node::Term::NullLiteral(_))));
+            }
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+
+        match variable_value(&statements[1]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert_eq!(operation.op, "??");
+                assert!(matches!(&*operation.left, node::Expression::MemberAccess(access) if access.null_safe && access.member == "name"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        // `??` groups to the right
+        match variable_value(&statements[2]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert!(matches!(&*operation.left, node::Expression::MethodCall(call) if call.null_safe && call.name == "size"));
+                assert!(matches!(&*operation.right, node::Expression::BinaryOperation(right) if right.op == "??"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        // `1 + 2 * 3 == 7` groups as `(1 + (2 * 3)) == 7`
+        match variable_value(&statements[3]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert_eq!(operation.op, "==");
+                assert!(matches!(&*operation.left, node::Expression::BinaryOperation(left) if left.op == "+"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        assert!(program.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_range_expressions() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    Range a = 0..n + 1;
+                    Range b = start..=end;
+                    Range c = ..10;
+                    Range d = 5..;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        let ranges: Vec<_> = statements.iter().map(|statement| match variable_value(statement) {
+            node::Expression::Range(range) => range,
+            value => panic!("Expected a range, found {:?}", value),
+        }).collect();
+
+        // Additive operators bind tighter than ranges
+        assert!(matches!(&*ranges[0].end, node::Expression::BinaryOperation(operation) if operation.op == "+"));
+        assert!(!ranges[0].inclusive);
+        assert!(ranges[1].inclusive);
+        assert!(matches!(&*ranges[1].start, node::Expression::Term(node::Term::VariableReference(start)) if start.value == "start"));
+        assert!(matches!(*ranges[2].start, node::Expression::Null(_)));
+        assert!(matches!(*ranges[3].end, node::Expression::Null(_)));
+
+        assert!(program.diagnostics.is_empty());
+        let program = parse("namespace Program { void main() { var r = 0..1..2; var s = ..1..=2; } }");
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Ranges can't be chained, `a..b..c` has no meaning"; 2]);
+    }
+
+    #[test]
+    fn test_bitwise_and_shift_operators() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    int a = flags & mask | 1 << 4;
+                    int b = ~a ^ b >> 2;
+                    bool c = a & 1 == 0 && b != 0;
+                    a |= 0x80;
+                    a <<= 1;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        // `flags & mask | 1 << 4` groups as `(flags & mask) | (1 << 4)`
+        match variable_value(&statements[0]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert_eq!(operation.op, "|");
+                assert!(matches!(&*operation.left, node::Expression::BinaryOperation(left) if left.op == "&"));
+                assert!(matches!(&*operation.right, node::Expression::BinaryOperation(right) if right.op == "<<"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        // `~a ^ b >> 2` groups as `(~a) ^ (b >> 2)`
+        match variable_value(&statements[1]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert_eq!(operation.op, "^");
+                assert!(matches!(&*operation.left, node::Expression::UnaryOperation(left) if left.op == "~"));
+                assert!(matches!(&*operation.right, node::Expression::BinaryOperation(right) if right.op == ">>"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        // Like C, `&` binds looser than `==`
+        match variable_value(&statements[2]) {
+            node::Expression::BinaryOperation(operation) => {
+                assert_eq!(operation.op, "&&");
+                assert!(matches!(&*operation.left, node::Expression::BinaryOperation(left) if left.op == "&"));
+            }
+            value => panic!("Expected a binary operation, found {:?}", value),
+        }
+
+        let ops: Vec<_> = statements[3..].iter().map(|statement| match statement {
+            node::Statement::VariableRedefinition(redefinition) => redefinition.op.as_str(),
+            statement => panic!("Expected a variable redefinition, found {:?}", statement),
+        }).collect();
+        assert_eq!(ops, vec!["|=", "<<="]);
+    }
+
+    #[test]
+    fn test_tuples_and_destructuring() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    (int x, int y) = point();
+                    (int, string)? pair = (1, "one");
+                    ((int a, int b), string c) = nested();
+                    int d = (1 + 2) * 3;
+                }
+
+                (int, int) point() {
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::VariableDefinition(definition) => {
+                assert_eq!(type_name(&definition.dtype).as_deref(), Some("(int, int)"));
+                match &definition.binding {
+                    node::Binding::Tuple(elements) => {
+                        assert!(matches!(&elements[1].binding, node::Binding::Name(name) if name == "y"));
+                    }
+                    binding => panic!("Expected a tuple binding, found {:?}", binding),
+                }
+            }
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+
+        match &statements[1] {
+            node::Statement::VariableDefinition(definition) => {
+                assert_eq!(type_name(&definition.dtype).as_deref(), Some("(int, string)?"));
+                assert!(matches!(&definition.value, node::Expression::Term(node::Term::TupleLiteral(tuple)) if tuple.values.len() == 2));
+            }
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+
+        match &statements[2] {
+            node::Statement::VariableDefinition(definition) => assert_eq!(type_name(&definition.dtype).as_deref(), Some("((int, int), string)")),
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+
+        // Parentheses without a comma only group
+        assert!(matches!(variable_value(&statements[3]), node::Expression::BinaryOperation(operation) if operation.op == "*"));
+
+        match &program.namespaces[0].definitions[1] {
+            node::Definition::FunctionDefinition(function) => assert_eq!(function.ftype.to_string(), "(int, int)"),
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+    }
+
+    #[test]
+    fn test_return_statements() {
+        let code = r#"
+            namespace Program {
+                int square(int x) {
+                    return x * x;
+                }
+
+                void main() {
+                    return;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+
+        match &function_statements(&program)[0] {
+            node::Statement::Return(statement) => assert!(matches!(&statement.value, node::Expression::BinaryOperation(operation) if operation.op == "*")),
+            statement => panic!("Expected a return statement, found {:?}", statement),
+        }
+
+        match &program.namespaces[0].definitions[1] {
+            node::Definition::FunctionDefinition(function) => {
+                assert_eq!(function.body.statements.len(), 1);
+                assert!(matches!(&function.body.statements[0], node::Statement::Return(statement) if matches!(statement.value, node::Expression::Null(_))));
+            }
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+    }
+
+    #[test]
+    fn test_for_loops() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    for (int i = 0; i < n; i += 1) {
+                        core.io.writeLn(i);
+                    }
+                    for (int x in items) {
+                        total += x;
+                    }
+                    for (;;) {
+                    }
+                    for (;; i++) {
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+        assert_eq!(statements.len(), 4);
+
+        match &statements[0] {
+            node::Statement::ForStatement(statement) => {
+                assert!(matches!(&*statement.initializer, node::Statement::VariableDefinition(_)));
+                assert!(matches!(&statement.condition, node::Expression::BinaryOperation(operation) if operation.op == "<"));
+                assert!(matches!(&*statement.update, node::Statement::VariableRedefinition(update) if update.op == "+="));
+                assert_eq!(statement.body.statements.len(), 1);
+            }
+            statement => panic!("Expected a for statement, found {:?}", statement),
+        }
+
+        match &statements[1] {
+            node::Statement::ForEachStatement(statement) => {
+                assert!(matches!(&statement.variable.binding, node::Binding::Name(name) if name == "x"));
+                assert!(matches!(&statement.iterable, node::Expression::Term(node::Term::VariableReference(items)) if items.value == "items"));
+            }
+            statement => panic!("Expected a for-each statement, found {:?}", statement),
+        }
+
+        match &statements[2] {
+            node::Statement::ForStatement(statement) => {
+                assert!(matches!(*statement.initializer, node::Statement::Null(_)));
+                assert!(matches!(statement.condition, node::Expression::Null(_)));
+                assert!(matches!(*statement.update, node::Statement::Null(_)));
+            }
+            statement => panic!("Expected a for statement, found {:?}", statement),
+        }
+
+        match &statements[3] {
+            node::Statement::ForStatement(statement) => {
+                assert!(matches!(*statement.initializer, node::Statement::Null(_)));
+                assert!(matches!(statement.condition, node::Expression::Null(_)));
+                assert!(matches!(&*statement.update, node::Statement::Expression(node::Expression::UnaryOperation(_))));
+            }
+            statement => panic!("Expected a for statement, found {:?}", statement),
+        }
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        // Missing delimiters are reported where they should have been
+        let program = parse("namespace Program { void main() { for (int x in items { f(); } for (int i = 0; i < 3 i++) { } } }");
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expected `)` but found `{`", "Expected `;` but found `i`"]);
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    outer: while (true) {
+                        for (int x in items) {
+                            if (x == 0) {
+                                continue;
+                            }
+                            break outer;
+                        }
+                    }
+                    break;
+                    while (true) {
+                        continue inner;
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::WhileStatement(statement) => {
+                assert_eq!(statement.label.as_deref(), Some("outer"));
+                match &statement.body.statements[0] {
+                    node::Statement::ForEachStatement(inner) => {
+                        assert!(matches!(&inner.body.statements[1], node::Statement::Break(jump) if jump.label.as_deref() == Some("outer")));
+                    }
+                    statement => panic!("Expected a for-each statement, found {:?}", statement),
+                }
+            }
+            statement => panic!("Expected a while statement, found {:?}", statement),
+        }
+
+        // The stray `break` and the unknown `inner` label are both reported
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "`break` outside of a loop",
+            "`continue inner` doesn't match any enclosing loop label",
+        ]);
+    }
+
+    #[test]
+    fn test_if_elif_else_chain() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    if (i == 0) {
+                        a = 1;
+                    } elif (i == 1) {
+                        a = 2;
+                    } elif (i == 2) {
+                        a = 3;
+                    } else {
+                        a = 4;
+                    }
+                    b = 5;
+                    else {
+                        a = 6;
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::IfStatement(statement) => {
+                assert_eq!(statement.body.statements.len(), 1);
+                assert_eq!(statement.elifs.len(), 2);
+                assert!(matches!(&statement.elifs[1].condition, node::Expression::BinaryOperation(operation) if operation.op == "=="));
+                assert_eq!(statement.else_statement.as_ref().map(|branch| branch.body.statements.len()), Some(1));
+            }
+            statement => panic!("Expected an if statement, found {:?}", statement),
+        }
+
+        // The statement after the chain isn't swallowed by it
+        assert!(matches!(&statements[1], node::Statement::VariableRedefinition(redefinition) if redefinition.name == "b"));
+
+        assert_eq!(program.diagnostics.len(), 1);
+        assert_eq!(program.diagnostics[0].message, "`else` without a matching `if`");
+    }
+
+    #[test]
+    fn test_expression_statements() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    new Foo().run();
+                    list.first().next?.visit(1, 2);
+                    i++;
+                    --j;
+                    handlers[0](event);
+                    core.io.writeLn("Hello");
+                    a + b;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+        assert_eq!(statements.len(), 7);
+
+        match &statements[0] {
+            node::Statement::Expression(node::Expression::MethodCall(call)) => {
+                assert_eq!(call.name, "run");
+                assert!(matches!(&*call.object, node::Expression::New(new) if new.class == node::TypeExpr::Named("Foo".to_string())));
+            }
+            statement => panic!("Expected a method call, found {:?}", statement),
+        }
+
+        match &statements[1] {
+            node::Statement::Expression(node::Expression::MethodCall(call)) => {
+                assert!(call.null_safe);
+                assert_eq!(call.arguements.len(), 2);
+                assert!(matches!(&*call.object, node::Expression::MemberAccess(access) if access.member == "next"));
+            }
+            statement => panic!("Expected a method call, found {:?}", statement),
+        }
+
+        assert!(matches!(&statements[2], node::Statement::Expression(node::Expression::UnaryOperation(operation)) if operation.op == "++" && operation.postfix));
+        assert!(matches!(&statements[3], node::Statement::Expression(node::Expression::UnaryOperation(operation)) if operation.op == "--" && !operation.postfix));
+        assert!(matches!(&statements[4], node::Statement::Expression(node::Expression::Call(call)) if matches!(*call.callee, node::Expression::Index(_))));
+        assert!(matches!(&statements[5], node::Statement::FunctionCall(call) if call.name == "core.io.writeLn"));
+
+        // Only `a + b;` is flagged
+        assert_eq!(program.diagnostics.len(), 1);
+        assert_eq!(program.diagnostics[0].level, node::DiagnosticLevel::Warning);
+        // A missing `]` is reported instead of swallowing the `;`
+        let program = parse("namespace Program { void main() { x = a[1; } }");
+        assert_eq!(program.diagnostics[0].message, "Expected `]` but found `;`");
+        // Indexes and members can be assigned to
+        let program = parse("namespace Program { void main() { a[i] = 5; grid.rows[0] += 1; a + b = 3; } }");
+        let statements = function_statements(&program);
+        assert_eq!(statements.len(), 3);
+        match &statements[0] {
+            node::Statement::Assignment(assignment) => {
+                assert!(matches!(assignment.target, node::Expression::Index(_)));
+                assert_eq!(assignment.op, "=");
+                assert!(matches!(assignment.value, node::Expression::Term(node::Term::IntegerLiteral(_))));
+            }
+            statement => panic!("Expected an assignment, found {:?}", statement),
+        }
+        assert!(matches!(&statements[1], node::Statement::Assignment(assignment) if assignment.op == "+="));
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Only names, members and indexes can be assigned to"]);
+
+        // Comments can appear anywhere
+        let program = parse("namespace Program { // entry point\n void main() { // start\n run(); // done\n } }");
+        assert_eq!(function_statements(&program).len(), 1);
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        // Leftover tokens are reported instead of being dropped
+        let program = parse("namespace Program { void main() { a 5; ) } }");
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expression statement has no effect", "Expected `;` but found `5`", "Expression statement has no effect", "Expected a statement but found `)`"]);
+
+        // Every simple statement needs its `;`, not just expressions
+        let program = parse("namespace Program { void main() { x = 1 y = 2; int a = 1 int b = 2; } int f() { return 1 } }");
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expected `;` but found `y`", "Expected `;` but found `int`", "Expected `;` but found `}`"]);
+    }
+
+    #[test]
+    fn test_nested_blocks() {
+        let code = "namespace Program {\n    void main() {\n        {\n            int a = 1;\n        }\n    }\n}";
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::Block(block) => {
+                assert_eq!(block.statements.len(), 1);
+                assert_eq!(block.open_brace.line, 3);
+                assert_eq!(block.close_brace.line, 5);
+                assert_eq!(&code[block.span.start..block.span.end], "{\n            int a = 1;\n        }");
+            }
+            statement => panic!("Expected a block, found {:?}", statement),
+        }
+        // Unterminated blocks and argument lists are reported instead of looping forever
+        let program = parse("namespace Program { fn main() {");
+        assert_eq!(program.diagnostics[0].message, "Expected `}` but reached the end of the file");
+
+        let program = parse("namespace Program { fn main() { f(1, 2");
+        assert_eq!(program.diagnostics[0].message, "Expected `)` but reached the end of the file");
+
+        let program = parse("namespace Program { fn main() { f(\"abc); } }");
+        assert!(! program.diagnostics.is_empty());
+
+        let program = parse("namespace A { class B {");
+        let messages: Vec<&str> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expected `}` but reached the end of the file"; 2]);
+    }
+
+    #[test]
+    fn test_match_statements() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    match (state) {
+                        1 => start(),
+                        2 | 3 => {
+                            step();
+                        }
+                        Shape.Circle(r) => draw(r),
+                        Color.Red => paint(),
+                        other => log(other),
+                        _ => stop(),
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+
+        let arms = match &function_statements(&program)[0] {
+            node::Statement::Match(statement) => &statement.arms,
+            statement => panic!("Expected a match statement, found {:?}", statement),
+        };
+        assert_eq!(arms.len(), 6);
+
+        assert!(matches!(&arms[0].pattern, node::Pattern::Literal(node::Term::IntegerLiteral(literal)) if literal.value == 1));
+        assert!(matches!(&arms[0].body, node::Statement::FunctionCall(call) if call.name == "start"));
+        assert!(matches!(&arms[1].pattern, node::Pattern::Alternative(alternatives) if alternatives.len() == 2));
+        assert!(matches!(&arms[1].body, node::Statement::Block(_)));
+        assert!(matches!(&arms[2].pattern, node::Pattern::EnumVariant(variant) if variant.name == "Shape.Circle" && matches!(&variant.fields[0], node::Pattern::Binding(name) if name == "r")));
+        assert!(matches!(&arms[3].pattern, node::Pattern::EnumVariant(variant) if variant.fields.is_empty()));
+        assert!(matches!(&arms[4].pattern, node::Pattern::Binding(name) if name == "other"));
+        assert!(matches!(&arms[5].pattern, node::Pattern::Wildcard));
+
+        assert!(program.diagnostics.is_empty());
+
+        let program = parse("namespace Program { void main() { match x { 1 => a(), } } }");
+        assert_eq!(program.diagnostics[0].message, "Expected `(` but found `x`");
+
+        let program = parse("namespace Program { void main() { match (x) { Shape.Circle(r => a(), } } }");
+        assert_eq!(program.diagnostics[0].message, "Expected `)` but found `=>`");
+
+        let program = parse("namespace Program { void main() { match (x) { 1 => a()");
+        assert_eq!(program.diagnostics.len(), 3);
+        assert!(program.diagnostics.iter().all(|diagnostic| diagnostic.message == "Expected `}` but reached the end of the file"));
+    }
+
+    #[test]
+    fn test_exceptions() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    try {
+                        file.read();
+                    } catch (IOError e) {
+                        throw new WrappedError(e);
+                    } catch (FormatError e) {
+                        log(e);
+                    } finally {
+                        file.close();
+                    }
+                    try {
+                        risky();
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        match &statements[0] {
+            node::Statement::Try(statement) => {
+                assert_eq!(statement.catches.len(), 2);
+                let parameter = statement.catches[0].parameter.as_ref().unwrap();
+                assert_eq!(type_name(&parameter.dtype).as_deref(), Some("IOError"));
+                assert!(matches!(&statement.catches[0].body.statements[0], node::Statement::Throw(throw) if matches!(throw.value, node::Expression::New(_))));
+                assert!(statement.finally.is_some());
+            }
+            statement => panic!("Expected a try statement, found {:?}", statement),
+        }
+
+        assert_eq!(program.diagnostics.len(), 1);
+        assert_eq!(program.diagnostics[0].message, "`try` needs at least one `catch` or a `finally`");
+
+        let program = parse("namespace Program { void main() { try { } catch (Error e { } } }");
+        assert_eq!(program.diagnostics[0].message, "Expected `)` but found `{`");
+
+        let program = parse("namespace Program { void main() { try { } catch e { } } }");
+        assert_eq!(program.diagnostics[0].message, "Expected `(` or `{` after `catch` but found `e`");
+
+        let program = parse("namespace Program { void main() { throw; } void f() { throw } }");
+        let messages: Vec<&str> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["`throw` needs a value", "`throw` needs a value", "Expected `;` but found `}`"]);
+    }
+
+    #[test]
+    fn test_do_while_and_loop() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    do {
+                        i += 1;
+                    } while (i < 10);
+                    events: loop {
+                        if (done()) {
+                            break events;
+                        }
+                    }
+                    i = 0;
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+        assert_eq!(statements.len(), 3);
+
+        match &statements[0] {
+            node::Statement::DoWhileStatement(statement) => {
+                assert_eq!(statement.body.statements.len(), 1);
+                assert!(matches!(&statement.condition, node::Expression::BinaryOperation(operation) if operation.op == "<"));
+            }
+            statement => panic!("Expected a do-while statement, found {:?}", statement),
+        }
+
+        assert!(matches!(&statements[1], node::Statement::LoopStatement(statement) if statement.label.as_deref() == Some("events")));
+        assert!(program.diagnostics.is_empty());
+
+        // The condition keeps its parentheses and the loop still ends with a `;`
+        let program = parse("namespace Program { void main() { do { } while x; y(); do { } while (x) z(); } }");
+        assert!(matches!(&function_statements(&program)[1], node::Statement::FunctionCall(call) if call.name == "y"));
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expected `(` but found `x`", "Expected `)` but found `;`", "Expected `;` but found `z`"]);
+    }
+
+    #[test]
+    fn test_let_var_and_const() {
+        let code = r#"
+            namespace Program {
+                void main(int count) {
+                    let x = 5;
+                    var y = "text";
+                    const int LIMIT = 10;
+                    let (a, b) = point();
+                    y = "more";
+                    count += 1;
+                    x = 6;
+                    x++;
+                    --x;
+                    y++;
+                    {
+                        var x = 1;
+                        x = 2;
+                    }
+                    LIMIT = 11;
+                    const MISSING;
+                }
+
+                pub const int MAX = 100;
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        let definitions: Vec<_> = statements[..4].iter().map(|statement| match statement {
+            node::Statement::VariableDefinition(definition) => (definition.mutability, type_name(&definition.dtype)),
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }).collect();
+        assert_eq!(definitions, vec![
+            (node::Mutability::Immutable, None),
+            (node::Mutability::Mutable, None),
+            (node::Mutability::Constant, Some("int".to_string())),
+            (node::Mutability::Immutable, None),
+        ]);
+
+        match &program.namespaces[0].definitions[1] {
+            node::Definition::VariableDefinition(definition) => {
+                assert_eq!(definition.visibility, node::Visibility::Public);
+                assert_eq!(definition.mutability, node::Mutability::Constant);
+            }
+            definition => panic!("Expected a variable definition, found {:?}", definition),
+        }
+
+        // The shadowing `var x` in the inner block can be reassigned
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Cannot reassign `x`, it was defined with `let`",
+            "Cannot reassign `x`, it was defined with `let`",
+            "Cannot reassign `x`, it was defined with `let`",
+            "Cannot reassign the constant `LIMIT`",
+            "Constants need a value",
+        ]);
+
+        // For-each variables take their type from the iterable
+        let program = parse("namespace Program { void main() { for (let x in items) { x = 1; } for (var y in items) { } } }");
+        let statements = function_statements(&program);
+        let variables: Vec<_> = statements.iter().map(|statement| match statement {
+            node::Statement::ForEachStatement(statement) => (statement.variable.mutability, type_name(&statement.variable.dtype)),
+            statement => panic!("Expected a for-each statement, found {:?}", statement),
+        }).collect();
+        assert_eq!(variables, vec![(node::Mutability::Immutable, None), (node::Mutability::Mutable, None)]);
+
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Cannot reassign `x`, it was defined with `let`"]);
+
+        // Namespace constants and class `let` fields are checked too, even when defined further down
+        let code = r#"
+            namespace A {
+                const int MAX = 1;
+
+                fn main() {
+                    MAX = 2;
+                    LATER = 3;
+                }
+
+                fn shadow(int MAX) {
+                    MAX = 4;
+                }
+
+                class Counter {
+                    let int start = 0;
+                    int count = 0;
+
+                    fn reset() {
+                        start = 1;
+                        count = 0;
+                        this.start = 1;
+                        this.count++;
+                        this.limit = 5;
+                    }
+
+                    fn rename(string start) {
+                        this.start = 2;
+                    }
+
+                    let int limit = 10;
+                }
+
+                let int LATER = 0;
+            }
+        "#;
+
+        let program = parse(code);
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec![
+            "Cannot reassign the constant `MAX`",
+            "Cannot reassign `start`, it was defined with `let`",
+            "Cannot reassign `this.start`, it was defined with `let`",
+            "Cannot reassign `this.start`, it was defined with `let`",
+            "Cannot reassign `this.limit`, it was defined with `let`",
+            "Cannot reassign `LATER`, it was defined with `let`",
+        ]);
+    }
+
+    #[test]
+    fn test_defer_statements() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    var file = open("log.txt");
+                    defer file.close();
+                    defer {
+                        flush();
+                        unlock();
+                    }
+                    if (verbose) {
+                        defer log("done");
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+
+        let body = match &program.namespaces[0].definitions[0] {
+            node::Definition::FunctionDefinition(function) => &function.body,
+            definition => panic!("Expected a function, found {:?}", definition),
+        };
+
+        // The defer inside the `if` belongs to that block
+        let defers: Vec<_> = body.defers().collect();
+        assert_eq!(defers.len(), 2);
+        assert!(matches!(&*defers[0].body, node::Statement::FunctionCall(call) if call.name == "file.close"));
+        assert!(matches!(&*defers[1].body, node::Statement::Block(block) if block.statements.len() == 2));
+
+        match &body.statements[3] {
+            node::Statement::IfStatement(statement) => assert_eq!(statement.body.defers().count(), 1),
+            statement => panic!("Expected an if statement, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_type_expressions() {
+        let code = r#"
+            namespace Program {
+                void main() {
+                    int[] a = null;
+                    List<string> b = new List<string>();
+                    Map<string, int[]> c = new Map<string, int[]>();
+                    Map<string, List<int>> d = null;
+                    Foo? e = null;
+                    (int, int) -> bool f = compare;
+                    int?[][] g = null;
+                    bool h = a < b;
+                    () -> int i = g;
+                }
+
+                List<int>? numbers() {
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let statements = function_statements(&program);
+
+        let types: Vec<_> = statements.iter().map(|statement| match statement {
+            node::Statement::VariableDefinition(definition) => type_name(&definition.dtype).unwrap(),
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }).collect();
+        assert_eq!(types, vec!["int[]", "List<string>", "Map<string, int[]>", "Map<string, List<int>>", "Foo?", "(int, int) -> bool", "int?[][]", "bool", "() -> int"]);
+
+        match &statements[3] {
+            node::Statement::VariableDefinition(definition) => match &definition.dtype {
+                Some(node::TypeExpr::Generic(generic)) => {
+                    assert_eq!(generic.name, "Map");
+                    assert!(matches!(&generic.arguments[1], node::TypeExpr::Generic(inner) if inner.arguments == vec![node::TypeExpr::Named("int".to_string())]));
+                }
+                dtype => panic!("Expected a generic type, found {:?}", dtype),
+            },
+            statement => panic!("Expected a variable definition, found {:?}", statement),
+        }
+
+        assert!(matches!(variable_value(&statements[1]), node::Expression::New(new) if new.class.to_string() == "List<string>"));
+        assert!(matches!(variable_value(&statements[7]), node::Expression::BinaryOperation(operation) if operation.op == "<"));
+
+        match &program.namespaces[0].definitions[1] {
+            node::Definition::FunctionDefinition(function) => assert_eq!(function.ftype.to_string(), "List<int>?"),
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+    }
+
+    #[test]
+    fn test_fn_declarations() {
+        let code = r#"
+            namespace Program {
+                fn main() {
+                }
+
+                pub fn add(int a, int b) -> int {
+                    return a + b;
+                }
+
+                fn log(string message) -> void {
+                }
+
+                fn compare() -> (int, int) -> bool {
+                }
+
+                int legacy(int a) {
+                }
+            }
+        "#;
+
+        let program = parse(code);
+
+        let functions: Vec<_> = program.namespaces[0].definitions.iter().map(|definition| match definition {
+            node::Definition::FunctionDefinition(function) => (function.name.as_str(), function.ftype.to_string(), function.arguements.len()),
+            definition => panic!("Expected a function, found {:?}", definition),
+        }).collect();
+        assert_eq!(functions, vec![
+            ("main", "void".to_string(), 0),
+            ("add", "int".to_string(), 2),
+            ("log", "void".to_string(), 1),
+            ("compare", "(int, int) -> bool".to_string(), 0),
+            ("legacy", "int".to_string(), 1),
+        ]);
+
+        match &program.namespaces[0].definitions[1] {
+            node::Definition::FunctionDefinition(function) => {
+                assert_eq!(function.visibility, node::Visibility::Public);
+                assert_eq!(function.ftype, node::TypeExpr::Named("int".to_string()));
+            }
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("namespace Program { int twice(int a) -> int { } }");
+        assert_eq!(program.diagnostics[0].message, "`twice` already has a return type before its name, remove the `->`");
+
+        let program = parse("namespace Program { fn f() -> { } class B : { } }");
+        let messages: Vec<&str> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Expected a type but found `{`"; 2]);
+    }
+
+    #[test]
+    fn test_inheritance_and_interfaces() {
+        let code = r#"
+            namespace Animals {
+                pub interface IPet : INamed {
+                    fn name() -> string;
+                    void play(int minutes);
+                }
+
+                class Animal {
+                }
+
+                class Dog : Animal, IPet, IComparable<Dog> {
+                    fn name() -> string {
+                        return "dog";
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let definitions = &program.namespaces[0].definitions;
+
+        match &definitions[0] {
+            node::Definition::InterfaceDefinition(interface) => {
+                assert_eq!(interface.visibility, node::Visibility::Public);
+                assert_eq!(interface.name, "IPet");
+                assert_eq!(interface.bases, vec![node::TypeExpr::Named("INamed".to_string())]);
+                let methods: Vec<_> = interface.methods.iter().map(|method| (method.name.as_str(), method.ftype.to_string(), method.arguements.len())).collect();
+                assert_eq!(methods, vec![("name", "string".to_string(), 0), ("play", "void".to_string(), 1)]);
+            }
+            definition => panic!("Expected an interface, found {:?}", definition),
+        }
+
+        match &definitions[1] {
+            node::Definition::ClassDefinition(class) => assert!(class.bases.is_empty()),
+            definition => panic!("Expected a class, found {:?}", definition),
+        }
+
+        match &definitions[2] {
+            node::Definition::ClassDefinition(class) => {
+                let bases: Vec<_> = class.bases.iter().map(|base| base.to_string()).collect();
+                assert_eq!(bases, vec!["Animal", "IPet", "IComparable<Dog>"]);
+                assert_eq!(class.definitions.len(), 1);
+            }
+            definition => panic!("Expected a class, found {:?}", definition),
+        }
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("namespace Program { interface I { pub fn x(); static fn y(); pub static int z(); } }");
+        match &program.namespaces[0].definitions[0] {
+            node::Definition::InterfaceDefinition(interface) => {
+                let names: Vec<_> = interface.methods.iter().map(|method| method.name.as_str()).collect();
+                assert_eq!(names, vec!["x", "y", "z"]);
+            }
+            definition => panic!("Expected an interface, found {:?}", definition),
+        }
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Interface members can't have modifiers"; 3]);
+    }
+
+    #[test]
+    fn test_generics() {
+        let code = r#"
+            namespace Collections {
+                class Box<T> {
+                    T value;
+                }
+
+                class SortedList<T : IComparable<T>> : List<T> {
+                }
+
+                interface IMapper<T, U> {
+                    fn map(T value) -> U;
+                }
+
+                fn max<T : Comparable + Hashable>(T a, T b) -> T {
+                    bool smaller = a < b;
+                    List<Box<int>> boxes = convert<List<int>, Box<int>>(items);
+                    int m = max<int>(1, 2);
+                    bool both = a < b && c > (d);
+                    return m;
+                }
+
+                U first<T, U>(List<T> items) {
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let definitions = &program.namespaces[0].definitions;
+
+        match &definitions[0] {
+            node::Definition::ClassDefinition(class) => {
+                assert_eq!(class.type_parameters.len(), 1);
+                assert_eq!(class.type_parameters[0].name, "T");
+                assert!(class.type_parameters[0].constraints.is_empty());
+            }
+            definition => panic!("Expected a class, found {:?}", definition),
+        }
+
+        match &definitions[1] {
+            node::Definition::ClassDefinition(class) => {
+                assert_eq!(class.type_parameters[0].constraints[0].to_string(), "IComparable<T>");
+                assert_eq!(class.bases[0].to_string(), "List<T>");
+            }
+            definition => panic!("Expected a class, found {:?}", definition),
+        }
+
+        match &definitions[2] {
+            node::Definition::InterfaceDefinition(interface) => {
+                let names: Vec<_> = interface.type_parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+                assert_eq!(names, vec!["T", "U"]);
+            }
+            definition => panic!("Expected an interface, found {:?}", definition),
+        }
+
+        let function = match &definitions[3] {
+            node::Definition::FunctionDefinition(function) => function,
+            definition => panic!("Expected a function, found {:?}", definition),
+        };
+        let constraints: Vec<_> = function.type_parameters[0].constraints.iter().map(|constraint| constraint.to_string()).collect();
+        assert_eq!(constraints, vec!["Comparable", "Hashable"]);
+        assert_eq!(function.ftype.to_string(), "T");
+
+        let statements = &function.body.statements;
+        assert!(matches!(variable_value(&statements[0]), node::Expression::BinaryOperation(operation) if operation.op == "<"));
+        match variable_value(&statements[1]) {
+            node::Expression::Term(node::Term::FunctionCall(call)) => {
+                let arguments: Vec<_> = call.type_arguments.iter().map(|argument| argument.to_string()).collect();
+                assert_eq!(arguments, vec!["List<int>", "Box<int>"]);
+                assert_eq!(call.arguements.len(), 1);
+            }
+            value => panic!("Expected a generic call, found {:?}", value),
+        }
+        assert!(matches!(variable_value(&statements[2]), node::Expression::Term(node::Term::FunctionCall(call)) if call.type_arguments.len() == 1));
+        assert!(matches!(variable_value(&statements[3]), node::Expression::BinaryOperation(operation) if operation.op == "&&"));
+
+        match &definitions[4] {
+            node::Definition::FunctionDefinition(function) => {
+                assert_eq!(function.name, "first");
+                assert_eq!(function.type_parameters.len(), 2);
+            }
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+    }
+
+    #[test]
+    fn test_enum_definitions() {
+        let code = r#"
+            namespace Protocol {
+                pub enum Color { Red, Green, Blue = 5 }
+
+                enum Shape {
+                    Circle(float r),
+                    Rect(float w, float h),
+                    Empty,
+                }
+
+                class Connection {
+                    enum State { Idle, Open(Socket socket), Closed }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let definitions = &program.namespaces[0].definitions;
+
+        match &definitions[0] {
+            node::Definition::EnumDefinition(enumeration) => {
+                assert_eq!(enumeration.visibility, node::Visibility::Public);
+                let names: Vec<_> = enumeration.variants.iter().map(|variant| variant.name.as_str()).collect();
+                assert_eq!(names, vec!["Red", "Green", "Blue"]);
+                assert!(matches!(enumeration.variants[0].value, node::Expression::Null(_)));
+                assert!(matches!(&enumeration.variants[2].value, node::Expression::Term(node::Term::IntegerLiteral(literal)) if literal.value == 5));
+            }
+            definition => panic!("Expected an enum, found {:?}", definition),
+        }
+
+        match &definitions[1] {
+            node::Definition::EnumDefinition(enumeration) => {
+                let fields: Vec<_> = enumeration.variants.iter().map(|variant| variant.fields.len()).collect();
+                assert_eq!(fields, vec![1, 2, 0]);
+                assert_eq!(type_name(&enumeration.variants[1].fields[1].dtype).as_deref(), Some("float"));
+            }
+            definition => panic!("Expected an enum, found {:?}", definition),
+        }
+
+        match &definitions[2] {
+            node::Definition::ClassDefinition(class) => assert!(matches!(&class.definitions[0], node::Definition::EnumDefinition(enumeration) if enumeration.variants.len() == 3)),
+            definition => panic!("Expected a class, found {:?}", definition),
+        }
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("namespace Protocol { enum Bad { Pair(int a) = 1 } }");
+        assert_eq!(program.diagnostics[0].message, "Enum variant `Pair` can't have both fields and a value");
+    }
+
+    #[test]
+    fn test_struct_definitions() {
+        let code = r#"
+            namespace Geometry {
+                pub struct Point {
+                    pub float x = 0.0;
+                    float y;
+                    let label = "origin";
+                }
+
+                struct Pair<T> {
+                    T first;
+                    T second;
+                }
+
+                void main() {
+                    Point p = Point { x: 1, y: 2.5 };
+                    Point origin = Point {};
+                    Pair<int> pair = Pair { first: 1, second: Point { x: 3, y: 4 } };
+                }
+            }
+        "#;
+
+        let program = parse(code);
+        let definitions = &program.namespaces[0].definitions;
+
+        match &definitions[0] {
+            node::Definition::StructDefinition(structure) => {
+                assert_eq!(structure.visibility, node::Visibility::Public);
+                let fields: Vec<_> = structure.fields.iter().map(|field| (field.visibility, type_name(&field.dtype))).collect();
+                assert_eq!(fields, vec![
+                    (node::Visibility::Public, Some("float".to_string())),
+                    (node::Visibility::Private, Some("float".to_string())),
+                    (node::Visibility::Private, None),
+                ]);
+                assert!(matches!(structure.fields[0].value, node::Expression::Term(node::Term::FloatLiteral(_))));
+                assert!(matches!(structure.fields[1].value, node::Expression::Null(_)));
+            }
+            definition => panic!("Expected a struct, found {:?}", definition),
+        }
+
+        match &definitions[1] {
+            node::Definition::StructDefinition(structure) => assert_eq!(structure.type_parameters[0].name, "T"),
+            definition => panic!("Expected a struct, found {:?}", definition),
+        }
+
+        let statements = match &definitions[2] {
+            node::Definition::FunctionDefinition(function) => &function.body.statements,
+            definition => panic!("Expected a function, found {:?}", definition),
+        };
+        match variable_value(&statements[0]) {
+            node::Expression::Term(node::Term::StructLiteral(literal)) => {
+                assert_eq!(literal.name, "Point");
+                let names: Vec<_> = literal.fields.iter().map(|field| field.name.as_str()).collect();
+                assert_eq!(names, vec!["x", "y"]);
+            }
+            value => panic!("Expected a struct literal, found {:?}", value),
+        }
+        assert!(matches!(variable_value(&statements[1]), node::Expression::Term(node::Term::StructLiteral(literal)) if literal.fields.is_empty()));
+        assert!(matches!(variable_value(&statements[2]), node::Expression::Term(node::Term::StructLiteral(literal)) if matches!(literal.fields[1].value, node::Expression::Term(node::Term::StructLiteral(_)))));
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("namespace Geometry { struct Bad { int x; fn area() -> int { } } }");
+        assert_eq!(program.diagnostics[0].message, "Struct `Bad` can only contain fields");
+    }
+
+    #[test]
+    fn test_constructors_and_access_modifiers() {
+        let code = r#"
+            namespace Shop {
+                pub class Counter {
+                    private int count;
+                    protected static int instances = 0;
+                    internal string name;
+
+                    pub new(int start) {
+                        this.count = start;
+                        this.reset();
+                        Counter self = this;
+                    }
+
+                    static pub fn create() -> Counter {
+                        return new Counter(0);
+                    }
+                }
+            }
+        "#;
+
+        let program = parse(code);
+
+        let class = match &program.namespaces[0].definitions[0] {
+            node::Definition::ClassDefinition(class) => class,
+            definition => panic!("Expected a class, found {:?}", definition),
+        };
+        assert_eq!(class.visibility, node::Visibility::Public);
+
+        let fields: Vec<_> = class.definitions[..3].iter().map(|definition| match definition {
+            node::Definition::VariableDefinition(field) => (field.visibility, field.is_static),
+            definition => panic!("Expected a field, found {:?}", definition),
+        }).collect();
+        assert_eq!(fields, vec![
+            (node::Visibility::Private, false),
+            (node::Visibility::Protected, true),
+            (node::Visibility::Internal, false),
+        ]);
+
+        match &class.definitions[3] {
+            node::Definition::ConstructorDefinition(constructor) => {
+                assert_eq!(constructor.visibility, node::Visibility::Public);
+                assert_eq!(constructor.arguements.len(), 1);
+
+                let statements = &constructor.body.statements;
+                match &statements[0] {
+                    node::Statement::Assignment(assignment) => match &assignment.target {
+                        node::Expression::MemberAccess(access) => {
+                            assert_eq!(access.member, "count");
+                            assert!(matches!(*access.object, node::Expression::This(_)));
+                        }
+                        target => panic!("Expected a member of `this`, found {:?}", target),
+                    },
+                    statement => panic!("Expected an assignment, found {:?}", statement),
+                }
+                match &statements[1] {
+                    node::Statement::Expression(node::Expression::MethodCall(call)) => {
+                        assert_eq!(call.name, "reset");
+                        assert!(matches!(*call.object, node::Expression::This(_)));
+                    }
+                    statement => panic!("Expected a method call on `this`, found {:?}", statement),
+                }
+                assert!(matches!(variable_value(&statements[2]), node::Expression::This(_)));
+            }
+            definition => panic!("Expected a constructor, found {:?}", definition),
+        }
+
+        match &class.definitions[4] {
+            node::Definition::FunctionDefinition(function) => {
+                assert_eq!(function.visibility, node::Visibility::Public);
+                assert!(function.is_static);
+            }
+            definition => panic!("Expected a function, found {:?}", definition),
+        }
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        // Tokens that can't start a member are skipped instead of looping forever
+        for code in ["class C { ; }", "class C { x = 1; }", "class C { // note\n int x; }"] {
+            let program = parse(code);
+            assert!(program.diagnostics.iter().all(|diagnostic| diagnostic.message.starts_with("Expected a class member")), "{:?}", program.diagnostics);
+        }
+
+        let program = parse("namespace Shop { static class Bad { } new() { } }");
+        let messages: Vec<_> = program.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, vec!["Only fields and methods can be `static`", "Constructors can only be defined inside a class"]);
+    }
+
+    #[test]
+    fn test_nested_namespaces() {
+        let code = r#"
+            use core.io;
+
+            int version = 1;
+
+            namespace Company {
+                use core.collections;
+
+                namespace Tools.Text {
+                    fn trim() { }
+                }
+
+                class Employee { }
+            }
+        "#;
+
+        let program = parse(code);
+
+        assert_eq!(program.usestatements.len(), 1);
+        assert!(matches!(&program.definitions[0], node::Definition::VariableDefinition(_)));
+
+        let company = &program.namespaces[0];
+        assert_eq!(company.path, vec!["Company"]);
+        assert_eq!(company.usestatements.len(), 1);
+        assert!(matches!(&company.definitions[0], node::Definition::ClassDefinition(class) if class.name == "Employee"));
+
+        let text = &company.namespaces[0];
+        assert_eq!(text.name, "Tools.Text");
+        assert_eq!(text.path, vec!["Company", "Tools", "Text"]);
+        assert!(matches!(&text.definitions[0], node::Definition::FunctionDefinition(function) if function.name == "trim"));
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("use core.io; namespace App.Models; class User { } fn helper() { }");
+        let namespace = &program.namespaces[0];
+        assert!(namespace.file_scoped);
+        assert_eq!(namespace.path, vec!["App", "Models"]);
+        assert_eq!(namespace.definitions.len(), 2);
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("namespace A { namespace B; }");
+        assert_eq!(program.diagnostics[0].message, "File-scoped namespaces can't be nested, use braces instead");
+    }
+
+    #[test]
+    fn test_use_statements() {
+        let code = r#"
+            use core.io.writeLn as print;
+            use core.collections.{List, Map as Dictionary, text.{trim, split}};
+            use core.math.*;
+            pub use app.models.User;
+
+            namespace App {
+                pub use app.shared.*;
+            }
+        "#;
+
+        let program = parse(code);
+        let uses = &program.usestatements;
+
+        assert_eq!(uses[0].path, vec!["core", "io", "writeLn"]);
+        assert_eq!(uses[0].alias.as_deref(), Some("print"));
+        assert_eq!(uses[0].visibility, node::Visibility::Private);
+
+        assert_eq!(uses[1].path, vec!["core", "collections"]);
+        let members: Vec<_> = uses[1].group.iter().map(|member| (member.path.join("."), member.alias.as_deref())).collect();
+        assert_eq!(members, vec![("List".to_string(), None), ("Map".to_string(), Some("Dictionary")), ("text".to_string(), None)]);
+        assert_eq!(uses[1].group[2].group.len(), 2);
+
+        assert!(uses[2].glob);
+        assert_eq!(uses[2].path, vec!["core", "math"]);
+
+        assert_eq!(uses[3].visibility, node::Visibility::Public);
+        assert_eq!(uses[3].path, vec!["app", "models", "User"]);
+
+        let nested = &program.namespaces[0].usestatements[0];
+        assert_eq!(nested.visibility, node::Visibility::Public);
+        assert!(nested.glob);
+
+        assert!(program.diagnostics.is_empty(), "{:?}", program.diagnostics);
+
+        let program = parse("use core.math.* as m;");
+        assert_eq!(program.diagnostics[0].message, "Only a single item can be renamed with `as`");
+    }
+}
@@ -1,34 +1,69 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
+pub use crate::lexer::{SourceLoc, Span};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Program {
     pub namespaces: Vec<Namespace>,
-    pub usestatements: Vec<UseStatement>
+    pub usestatements: Vec<UseStatement>,
+    // Definitions outside of any namespace
+    pub definitions: Vec<Definition>,
+    pub diagnostics: Vec<Diagnostic>
 }
 impl Program {
     pub fn new() -> Self {
-        Program {namespaces: vec![], usestatements: vec![]}
+        Program {namespaces: vec![], usestatements: vec![], definitions: vec![], diagnostics: vec![]}
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DiagnosticLevel {
+    Warning,
+    Error
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+// Errors and warnings reported while parsing
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    pub location: SourceLoc
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `use a.b.c as d;`, `use a.b.{c, d};` or `use a.b.*;`, `pub use` re-exports the imported names
 pub struct UseStatement {
-    pub name: String
+    pub visibility: Visibility,
+    pub path: Vec<String>,
+    pub alias: Option<String>,
+    // `{c, d}`, each member's path continues from this one and may have its own alias or group
+    pub group: Vec<UseStatement>,
+    // `*`, imports everything under the path
+    pub glob: bool
 }
 impl UseStatement {
     pub fn new() -> Self {
-        UseStatement {name: "".to_string()}
+        UseStatement {visibility: Visibility::Private, path: vec![], alias: None, group: vec![], glob: false}
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Namespace {
+    // The name as written, eg. `B` or `A.B`
     pub name: String,
+    // The fully qualified path including enclosing namespaces, eg. `["Outer", "A", "B"]`
+    pub path: Vec<String>,
+    // `namespace A.B;`, which covers the rest of the file
+    pub file_scoped: bool,
+    pub namespaces: Vec<Namespace>,
+    pub usestatements: Vec<UseStatement>,
     pub definitions: Vec<Definition>
 }
 impl Namespace {
     pub fn new() -> Self {
-        Namespace {name: "".to_string(), definitions: vec![]}
+        Namespace {name: "".to_string(), path: vec![], file_scoped: false, namespaces: vec![], usestatements: vec![], definitions: vec![]}
     }
 }
 
@@ -37,69 +72,287 @@ impl Namespace {
 pub enum Definition {
     FunctionDefinition(FunctionDefinition),
     ClassDefinition(ClassDefinition),
+    InterfaceDefinition(InterfaceDefinition),
+    EnumDefinition(EnumDefinition),
+    StructDefinition(StructDefinition),
+    ConstructorDefinition(ConstructorDefinition),
     VariableDefinition(VariableDefinition)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FunctionDefinition {
-    pub public: bool,
-    pub ftype: String,
+    pub visibility: Visibility,
+    pub is_static: bool,
+    pub ftype: TypeExpr,
     pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
     pub arguements: Vec<VariableDefinition>,
-    pub statements: Vec<Statement>,
+    pub body: Block,
 }
 impl FunctionDefinition {
     pub fn new() -> Self {
-        FunctionDefinition {public: false, ftype: "".to_string(), name: "".to_string(), arguements: vec![], statements: vec![]}
+        FunctionDefinition {visibility: Visibility::Private, is_static: false, ftype: TypeExpr::Void, name: "".to_string(), type_parameters: vec![], arguements: vec![], body: Block::new()}
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Visibility {
+    // The default, only visible inside the enclosing class or namespace
+    Private,
+    // `protected`, also visible to derived classes
+    Protected,
+    // `internal`, visible anywhere in the same project
+    Internal,
+    // `pub`
+    Public
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `new(int x) { }` inside a class
+pub struct ConstructorDefinition {
+    pub visibility: Visibility,
+    pub arguements: Vec<VariableDefinition>,
+    pub body: Block
+}
+impl ConstructorDefinition {
+    pub fn new() -> Self {
+        ConstructorDefinition {visibility: Visibility::Private, arguements: vec![], body: Block::new()}
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClassDefinition {
-    pub public: bool,
+    pub visibility: Visibility,
     pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
+    // `class Dog : Animal, IPet`, the base class and implemented interfaces in the order written
+    pub bases: Vec<TypeExpr>,
     pub definitions: Vec<Definition>
 }
 impl ClassDefinition {
     pub fn new() -> Self {
-        ClassDefinition {name: "".to_string(), definitions: vec![], public: false}
+        ClassDefinition {name: "".to_string(), type_parameters: vec![], bases: vec![], definitions: vec![], visibility: Visibility::Private}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InterfaceDefinition {
+    pub visibility: Visibility,
+    pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
+    // Interfaces this one extends
+    pub bases: Vec<TypeExpr>,
+    pub methods: Vec<MethodSignature>
+}
+impl InterfaceDefinition {
+    pub fn new() -> Self {
+        InterfaceDefinition {visibility: Visibility::Private, name: "".to_string(), type_parameters: vec![], bases: vec![], methods: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// A value type, only fields are allowed in its body
+pub struct StructDefinition {
+    pub visibility: Visibility,
+    pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
+    pub fields: Vec<VariableDefinition>
+}
+impl StructDefinition {
+    pub fn new() -> Self {
+        StructDefinition {visibility: Visibility::Private, name: "".to_string(), type_parameters: vec![], fields: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumDefinition {
+    pub visibility: Visibility,
+    pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
+    pub variants: Vec<EnumVariant>
+}
+impl EnumDefinition {
+    pub fn new() -> Self {
+        EnumDefinition {visibility: Visibility::Private, name: "".to_string(), type_parameters: vec![], variants: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `Blue = 5` or `Rect(float w, float h)`
+pub struct EnumVariant {
+    pub name: String,
+    // The explicit discriminant, `Expression::Null` when there isn't one
+    pub value: Expression,
+    pub fields: Vec<VariableDefinition>
+}
+impl EnumVariant {
+    pub fn new() -> Self {
+        EnumVariant {name: "".to_string(), value: Expression::Null(Null {}), fields: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// A function without a body, eg. `fn speak() -> string;` inside an interface
+pub struct MethodSignature {
+    pub ftype: TypeExpr,
+    pub name: String,
+    pub type_parameters: Vec<TypeParameter>,
+    pub arguements: Vec<VariableDefinition>
+}
+impl MethodSignature {
+    pub fn new() -> Self {
+        MethodSignature {ftype: TypeExpr::Void, name: "".to_string(), type_parameters: vec![], arguements: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `T` or `T : Comparable + Hashable` in `class Box<T>` or `fn max<T>()`
+pub struct TypeParameter {
+    pub name: String,
+    pub constraints: Vec<TypeExpr>
+}
+impl TypeParameter {
+    pub fn new() -> Self {
+        TypeParameter {name: "".to_string(), constraints: vec![]}
     }
 }
 #[derive(Debug, Serialize, Deserialize)]
 //Statement and Definition
 pub struct VariableDefinition {
-    pub public: bool,
-    pub dtype: String,
-    pub name: String,
+    pub visibility: Visibility,
+    pub is_static: bool,
+    pub mutability: Mutability,
+    // `None` when the type is inferred from the value, eg. `let x = 5;`
+    pub dtype: Option<TypeExpr>,
+    pub binding: Binding,
     pub value: Expression
 }
 impl VariableDefinition {
     pub fn new() -> Self {
-        VariableDefinition {public: false, dtype: "".to_string(), name: "".to_string(), value: Expression::Null(Null {})}
+        VariableDefinition {visibility: Visibility::Private, is_static: false, mutability: Mutability::Mutable, dtype: None, binding: Binding::Name("".to_string()), value: Expression::Null(Null {})}
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Mutability {
+    // `int x` or `var x`
+    Mutable,
+    // `let x`, can't be reassigned
+    Immutable,
+    // `const x`
+    Constant
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// What a variable definition binds its value to
+pub enum Binding {
+    Name(String),
+    // `(int x, int y) = point();`, each element is typed and may destructure further
+    Tuple(Vec<VariableDefinition>)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+// The type written in a definition, eg. `int`, `int[]`, `Map<string, int[]>`, `Foo?` or `(int, int) -> bool`
+pub enum TypeExpr {
+    // `void`, or a function declared with `fn` and no `->`
+    Void,
+    Named(String),
+    Array(Box<TypeExpr>),
+    Generic(GenericType),
+    Nullable(Box<TypeExpr>),
+    Tuple(Vec<TypeExpr>),
+    Function(FunctionType)
+}
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeExpr::Void => write!(f, "void"),
+            TypeExpr::Named(name) => write!(f, "{}", name),
+            TypeExpr::Array(element) => write!(f, "{}[]", element),
+            TypeExpr::Generic(generic) => write!(f, "{}<{}>", generic.name, join_types(&generic.arguments)),
+            TypeExpr::Nullable(inner) => write!(f, "{}?", inner),
+            TypeExpr::Tuple(elements) => write!(f, "({})", join_types(elements)),
+            TypeExpr::Function(function) => write!(f, "({}) -> {}", join_types(&function.parameters), function.return_type),
+        }
+    }
+}
+
+fn join_types(types: &[TypeExpr]) -> String {
+    return types.iter().map(|dtype| dtype.to_string()).collect::<Vec<_>>().join(", ");
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+// `List<string>`
+pub struct GenericType {
+    pub name: String,
+    pub arguments: Vec<TypeExpr>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+// `(int, int) -> bool`
+pub struct FunctionType {
+    pub parameters: Vec<TypeExpr>,
+    pub return_type: Box<TypeExpr>
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 // Statements
 pub enum Statement {
     VariableDefinition(VariableDefinition),
     FunctionCall(FunctionCall),
     VariableRedefinition(VariableRedefinition),
+    Assignment(Assignment),
+    Block(Block),
     IfStatement(IfStatement),
-    ElifStatement(ElifStatement),
-    ElseStatement(ElseStatement),
     WhileStatement(WhileStatement),
+    ForStatement(ForStatement),
+    ForEachStatement(ForEachStatement),
+    DoWhileStatement(DoWhileStatement),
+    LoopStatement(LoopStatement),
+    Expression(Expression),
+    Match(MatchStatement),
+    Throw(ThrowStatement),
+    Try(TryStatement),
+    Defer(DeferStatement),
+    Return(ReturnStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
     Null(Null)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+// A `{ ... }` list of statements, which is also the scope of any locals defined in it
+pub struct Block {
+    pub span: Span,
+    pub open_brace: SourceLoc,
+    pub close_brace: SourceLoc,
+    pub statements: Vec<Statement>
+}
+impl Block {
+    pub fn new() -> Self {
+        Block {span: Span::default(), open_brace: SourceLoc::default(), close_brace: SourceLoc::default(), statements: vec![]}
+    }
+
+    // The defers that run when this block exits, in source order. Nested blocks have their own.
+    pub fn defers(&self) -> impl Iterator<Item = &DeferStatement> {
+        self.statements.iter().filter_map(|statement| match statement {
+            Statement::Defer(defer) => Some(defer),
+            _ => None
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// A whole `if` / `elif` / `else` chain, branches are kept in source order
 pub struct IfStatement {
     pub condition: Expression,
-    pub statements: Vec<Statement>
+    pub body: Block,
+    pub elifs: Vec<ElifStatement>,
+    pub else_statement: Option<ElseStatement>
 }
 impl IfStatement {
     pub fn new() -> Self {
-        IfStatement {condition: Expression::Null(Null {}), statements: vec![]}
+        IfStatement {condition: Expression::Null(Null {}), body: Block::new(), elifs: vec![], else_statement: None}
     }
 }
 
@@ -114,45 +367,249 @@ impl ArrayLiteral {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TupleLiteral {
+    pub values: Vec<Expression>
+}
+impl TupleLiteral {
+    pub fn new() -> Self {
+        TupleLiteral {values: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `Point { x: 1, y: 2 }`, fields that are left out keep their defaults
+pub struct StructLiteral {
+    pub name: String,
+    pub fields: Vec<FieldInitializer>
+}
+impl StructLiteral {
+    pub fn new() -> Self {
+        StructLiteral {name: "".to_string(), fields: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `x: 1` inside a struct literal
+pub struct FieldInitializer {
+    pub name: String,
+    pub value: Expression
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ElifStatement {
     pub condition: Expression,
-    pub statements: Vec<Statement>
+    pub body: Block
 }
 impl ElifStatement {
     pub fn new() -> Self {
-        ElifStatement {condition: Expression::Null(Null {}), statements: vec![]}
+        ElifStatement {condition: Expression::Null(Null {}), body: Block::new()}
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ElseStatement {
-    pub statements: Vec<Statement>
+    pub body: Block
 }
 impl ElseStatement {
     pub fn new() -> Self {
-        ElseStatement {statements: vec![]}
+        ElseStatement {body: Block::new()}
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WhileStatement {
+    pub label: Option<String>,
     pub condition: Expression,
-    pub statements: Vec<Statement>
+    pub body: Block
 }
 impl WhileStatement {
     pub fn new() -> Self {
-        WhileStatement {condition: Expression::Null(Null {}), statements: vec![]}
+        WhileStatement {label: None, condition: Expression::Null(Null {}), body: Block::new()}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `for (int i = 0; i < n; i += 1) { }`, any of the three clauses can be left empty
+pub struct ForStatement {
+    pub label: Option<String>,
+    pub initializer: Box<Statement>,
+    pub condition: Expression,
+    pub update: Box<Statement>,
+    pub body: Block
+}
+impl ForStatement {
+    pub fn new() -> Self {
+        ForStatement {label: None, initializer: Box::new(Statement::Null(Null {})), condition: Expression::Null(Null {}), update: Box::new(Statement::Null(Null {})), body: Block::new()}
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+// `for (int x in items) { }`
+pub struct ForEachStatement {
+    pub label: Option<String>,
+    pub variable: VariableDefinition,
+    pub iterable: Expression,
+    pub body: Block
+}
+impl ForEachStatement {
+    pub fn new() -> Self {
+        ForEachStatement {label: None, variable: VariableDefinition::new(), iterable: Expression::Null(Null {}), body: Block::new()}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `match (value) { pattern => ..., }`, arms are tried in source order
+pub struct MatchStatement {
+    pub value: Expression,
+    pub arms: Vec<MatchArm>
+}
+impl MatchStatement {
+    pub fn new() -> Self {
+        MatchStatement {value: Expression::Null(Null {}), arms: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `pattern => statement` or `pattern => { ... }`
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Statement
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Pattern {
+    // `1`, `"text"`, `true`, `null`
+    Literal(Term),
+    // `_`
+    Wildcard,
+    // `x`, binds the matched value to a new local
+    Binding(String),
+    // `2 | 3`
+    Alternative(Vec<Pattern>),
+    // `Shape.Circle(r)` or `Color.Red`
+    EnumVariant(EnumVariantPattern)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumVariantPattern {
+    pub name: String,
+    pub fields: Vec<Pattern>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `throw value;`
+pub struct ThrowStatement {
+    pub value: Expression
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `try { } catch (IOError e) { } finally { }`
+pub struct TryStatement {
+    pub body: Block,
+    pub catches: Vec<CatchClause>,
+    pub finally: Option<Block>
+}
+impl TryStatement {
+    pub fn new() -> Self {
+        TryStatement {body: Block::new(), catches: vec![], finally: None}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `catch (IOError e) { }`, or `catch { }` to catch anything
+pub struct CatchClause {
+    pub parameter: Option<VariableDefinition>,
+    pub body: Block
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `defer expression;` or `defer { ... }`, run when the enclosing block exits
+pub struct DeferStatement {
+    pub body: Box<Statement>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `return value;`, the value is `Expression::Null` for a bare `return;`
+pub struct ReturnStatement {
+    pub value: Expression
+}
+impl ReturnStatement {
+    pub fn new() -> Self {
+        ReturnStatement {value: Expression::Null(Null {})}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `do { } while (condition);`, the condition is checked after each run of the body
+pub struct DoWhileStatement {
+    pub label: Option<String>,
+    pub body: Block,
+    pub condition: Expression
+}
+impl DoWhileStatement {
+    pub fn new() -> Self {
+        DoWhileStatement {label: None, body: Block::new(), condition: Expression::Null(Null {})}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `loop { }`, only left through `break`, `return` or `throw`
+pub struct LoopStatement {
+    pub label: Option<String>,
+    pub body: Block
+}
+impl LoopStatement {
+    pub fn new() -> Self {
+        LoopStatement {label: None, body: Block::new()}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `break;` or `break outer;`
+pub struct BreakStatement {
+    pub label: Option<String>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `continue;` or `continue outer;`
+pub struct ContinueStatement {
+    pub label: Option<String>
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Expression {
     BinaryOperation(BinaryOperation),
+    UnaryOperation(UnaryOperation),
+    MemberAccess(MemberAccess),
+    MethodCall(MethodCall),
+    Call(Call),
+    Index(Index),
+    New(NewExpression),
+    This(This),
+    Range(Range),
     Term(Term),
     Null(Null)
 }
 
+impl Expression {
+    // Whether evaluating the expression can do anything besides produce a value
+    pub fn has_side_effects(&self) -> bool {
+        match self {
+            Expression::BinaryOperation(operation) => operation.left.has_side_effects() || operation.right.has_side_effects(),
+            Expression::UnaryOperation(operation) => operation.op == "++" || operation.op == "--" || operation.operand.has_side_effects(),
+            Expression::MemberAccess(access) => access.object.has_side_effects(),
+            Expression::MethodCall(_) | Expression::Call(_) | Expression::New(_) => true,
+            Expression::Index(index) => index.object.has_side_effects() || index.index.has_side_effects(),
+            Expression::Range(range) => range.start.has_side_effects() || range.end.has_side_effects(),
+            Expression::Term(Term::FunctionCall(_)) => true,
+            Expression::Term(Term::TupleLiteral(tuple)) => tuple.values.iter().any(|value| value.has_side_effects()),
+            Expression::Term(Term::StructLiteral(literal)) => literal.fields.iter().any(|field| field.value.has_side_effects()),
+            Expression::Term(_) | Expression::This(_) | Expression::Null(_) => false
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Term {
     FunctionCall(FunctionCall),
@@ -163,17 +620,84 @@ pub enum Term {
     BooleanLiteral(BooleanLiteral),
     VariableReference(VariableReference),
     ArrayLiteral(ArrayLiteral),
+    TupleLiteral(TupleLiteral),
+    StructLiteral(StructLiteral),
+    NullLiteral(NullLiteral),
     Null(Null)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IntegerLiteral {
-    pub value: String
+    pub value: i128,
+    pub suffix: Option<NumericSuffix>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FloatLiteral {
-    pub value: String
+    pub value: f64,
+    pub suffix: Option<NumericSuffix>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+// Width suffixes on numeric literals, eg. `10u8` or `2.0f32`
+pub enum NumericSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64
+}
+impl NumericSuffix {
+    pub fn from_literal(suffix: &str) -> Option<Self> {
+        match suffix {
+            "i8" => Some(NumericSuffix::I8),
+            "i16" => Some(NumericSuffix::I16),
+            "i32" => Some(NumericSuffix::I32),
+            "i64" => Some(NumericSuffix::I64),
+            "u8" => Some(NumericSuffix::U8),
+            "u16" => Some(NumericSuffix::U16),
+            "u32" => Some(NumericSuffix::U32),
+            "u64" => Some(NumericSuffix::U64),
+            "f32" => Some(NumericSuffix::F32),
+            "f64" => Some(NumericSuffix::F64),
+            _ => None
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            NumericSuffix::I8 => "i8",
+            NumericSuffix::I16 => "i16",
+            NumericSuffix::I32 => "i32",
+            NumericSuffix::I64 => "i64",
+            NumericSuffix::U8 => "u8",
+            NumericSuffix::U16 => "u16",
+            NumericSuffix::U32 => "u32",
+            NumericSuffix::U64 => "u64",
+            NumericSuffix::F32 => "f32",
+            NumericSuffix::F64 => "f64"
+        }
+    }
+
+    // The smallest and largest values an integer of this width can hold
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        match self {
+            NumericSuffix::I8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            NumericSuffix::I16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            NumericSuffix::I32 => Some((i32::MIN as i128, i32::MAX as i128)),
+            NumericSuffix::I64 => Some((i64::MIN as i128, i64::MAX as i128)),
+            NumericSuffix::U8 => Some((0, u8::MAX as i128)),
+            NumericSuffix::U16 => Some((0, u16::MAX as i128)),
+            NumericSuffix::U32 => Some((0, u32::MAX as i128)),
+            NumericSuffix::U64 => Some((0, u64::MAX as i128)),
+            NumericSuffix::F32 | NumericSuffix::F64 => None
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -191,6 +715,9 @@ pub struct BooleanLiteral {
     pub value: String
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NullLiteral {}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VariableReference {
     pub value: String
@@ -199,25 +726,98 @@ pub struct VariableReference {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub name: String,
+    // `max<int>(a, b)`, empty when left to be inferred
+    pub type_arguments: Vec<TypeExpr>,
     pub arguements: Vec<Expression>
 }
 impl FunctionCall {
     pub fn new() -> Self {
-        FunctionCall {name: "".to_string(), arguements: vec![]}
+        FunctionCall {name: "".to_string(), type_arguments: vec![], arguements: vec![]}
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+// `object.member`, or `object?.member` when null safe
+pub struct MemberAccess {
+    pub object: Box<Expression>,
+    pub member: String,
+    pub null_safe: bool
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `object.name(...)`, or `object?.name(...)` when null safe
+pub struct MethodCall {
+    pub object: Box<Expression>,
+    pub name: String,
+    pub type_arguments: Vec<TypeExpr>,
+    pub arguements: Vec<Expression>,
+    pub null_safe: bool
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// Calls on anything that isn't a plain name, eg. `handlers[0](event)`
+pub struct Call {
+    pub callee: Box<Expression>,
+    pub arguements: Vec<Expression>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `object[index]`
+pub struct Index {
+    pub object: Box<Expression>,
+    pub index: Box<Expression>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `this`, the instance a method or constructor was called on
+pub struct This {}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `new Foo(...)`
+pub struct NewExpression {
+    pub class: TypeExpr,
+    pub arguements: Vec<Expression>
+}
+impl NewExpression {
+    pub fn new() -> Self {
+        NewExpression {class: TypeExpr::Named("".to_string()), arguements: vec![]}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `start..end` or `start..=end`, either end can be left open as `Expression::Null`
+pub struct Range {
+    pub start: Box<Expression>,
+    pub end: Box<Expression>,
+    pub inclusive: bool
+}
+impl Range {
+    pub fn new() -> Self {
+        Range {start: Box::new(Expression::Null(Null {})), end: Box::new(Expression::Null(Null {})), inclusive: false}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+// `name = value`, or a compound assignment such as `name += value`
 pub struct VariableRedefinition {
     pub name: String,
+    pub op: String,
     pub value: Expression
 }
 impl VariableRedefinition {
     pub fn new() -> Self {
-        VariableRedefinition {name: "".to_string(), value: Expression::Null(Null {})}
+        VariableRedefinition {name: "".to_string(), op: "=".to_string(), value: Expression::Null(Null {})}
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+// Assignment to anything that isn't a plain name, eg. `a[i] = value` or `this.count += 1`
+pub struct Assignment {
+    pub target: Expression,
+    pub op: String,
+    pub value: Expression
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BinaryOperation {
     pub left: Box<Expression>,
@@ -230,5 +830,18 @@ impl BinaryOperation {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnaryOperation {
+    pub op: String,
+    pub operand: Box<Expression>,
+    // `i++` rather than `++i`
+    pub postfix: bool,
+}
+impl UnaryOperation {
+    pub fn new() -> Self {
+        UnaryOperation {op: "".to_string(), operand: Box::new(Expression::Null(Null {})), postfix: false}
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Null {}
\ No newline at end of file
@@ -1,48 +1,132 @@
-use crate::{lexer::{SourceLoc, Token, TokenKind}, node::{BinaryOperation, BooleanLiteral, ClassDefinition, Definition, ElifStatement, ElseStatement, Expression, FloatLiteral, FunctionCall, FunctionDefinition, IfStatement, IntegerLiteral, Namespace, Null, Program, Statement, StringLiteral, Term, UseStatement, VariableDefinition, VariableRedefinition, VariableReference, WhileStatement}};
+use crate::{lexer::{Op, SourceLoc, Span, Token, TokenKind}, node::{Assignment, BinaryOperation, Binding, Block, BooleanLiteral, BreakStatement, Call, CatchClause, ClassDefinition, ConstructorDefinition, ContinueStatement, DeferStatement, Definition, Diagnostic, DiagnosticLevel, DoWhileStatement, ElifStatement, ElseStatement, EnumDefinition, EnumVariant, EnumVariantPattern, Expression, FieldInitializer, FloatLiteral, ForEachStatement, ForStatement, FunctionCall, FunctionDefinition, FunctionType, GenericType, IfStatement, Index, IntegerLiteral, InterfaceDefinition, LoopStatement, MatchArm, MatchStatement, MemberAccess, MethodCall, MethodSignature, Mutability, Namespace, NewExpression, Null, NullLiteral, NumericSuffix, Pattern, Program, Range, ReturnStatement, Statement, StringLiteral, StructDefinition, StructLiteral, Term, ThrowStatement, TryStatement, TupleLiteral, TypeExpr, TypeParameter, This, UnaryOperation, UseStatement, VariableDefinition, VariableRedefinition, VariableReference, Visibility, WhileStatement}};
 
 pub struct Parser<'a> {
     tokens: &'a [Token<'a>], // Changed to a slice for better flexibility
     i: usize,
+    diagnostics: Vec<Diagnostic>,
+    // Labels of the loops enclosing the statement being parsed, innermost last
+    loops: Vec<Option<String>>,
+    // Locals visible to the statement being parsed and how they were defined, innermost last
+    scopes: Vec<Vec<(String, Mutability)>>,
+    // Reassigned names that weren't defined yet, they may still turn out to be namespace or class members defined further down
+    unresolved: Vec<(String, SourceLoc)>,
+    // Index in `scopes` of the members of the class being parsed, `this.x` only looks there
+    class_scope: Option<usize>,
+    // Path of the namespace being parsed, eg. `["A", "B"]`
+    namespace_path: Vec<String>,
+    // Set while the current `>>` token has had its first `>` used to close a type argument list
+    split_gt: bool,
 }
 
 impl<'a> Parser<'a> {
     // Constructor to take a slice instead of a reference to Vec
     pub fn new(toks: &'a Vec<Token<'a>>) -> Self { // Pass in tokens with lifetime 'a
-        Parser { tokens: toks, i: 0 }
+        Parser { tokens: toks, i: 0, diagnostics: vec![], loops: vec![], scopes: vec![], unresolved: vec![], class_scope: None, namespace_path: vec![], split_gt: false }
     }
 
 
     pub fn parse(&mut self) -> Program {
         let mut program: Program = Program::new();
 
+        // Top-level definitions, namespaces and classes each get a scope for their members
+        self.scopes.push(vec![]);
+
         while self.i < self.tokens.len() {
             if self.match_token(0, TokenKind::NamespaceKw) {
                 program.namespaces.push(self.parse_namespace());
             }
-            else if self.match_token(0, TokenKind::UseKw) {
+            else if self.match_token(self.modifiers_length(0), TokenKind::UseKw) {
                 program.usestatements.push(self.parse_use_statement());
             }
+            else if let Some(definition) = self.parse_namespace_definition() {
+                program.definitions.push(definition);
+            }
         }
 
+        self.close_member_scope();
+
+        program.diagnostics = std::mem::take(&mut self.diagnostics);
+
         return program;
     }
 
     fn parse_use_statement(&mut self) -> UseStatement {
         let mut statement = UseStatement::new();
 
-        self.i+=1;
+        statement.visibility = self.parse_visibility();
 
-        statement.name = self.peek(0).literal.to_string();
+        // Skip the USE
 
         self.i+=1;
 
-        //Skip Semicolon
+        self.parse_use_tree(&mut statement);
 
-        self.i+=1;
+        self.expect(TokenKind::Semicolon, "`;`");
 
         return statement;
     }
 
+    // The part of a use statement after `use`, also used for each member of a `{...}` group
+    fn parse_use_tree(&mut self, statement: &mut UseStatement) {
+        if self.match_token(0, TokenKind::Identifier) {
+            // Dotted paths are a single token
+            statement.path = self.peek(0).literal.split('.').map(|part| part.to_string()).collect();
+
+            self.i+=1;
+        }
+        else {
+            self.error(format!("Expected a path but found `{}`", self.peek(0).literal));
+        }
+
+        // `a.b.*` and `a.b.{...}`, the lexer leaves the '.' before these on its own
+        if self.match_token(0, TokenKind::Dot) {
+            self.i+=1;
+
+            if self.match_token(0, TokenKind::Multiply) {
+                statement.glob = true;
+                self.i+=1;
+            }
+            else if self.match_token(0, TokenKind::OpenCurly) {
+                // Skip the '{'
+
+                self.i+=1;
+
+                while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+                    let mut member = UseStatement::new();
+                    self.parse_use_tree(&mut member);
+                    statement.group.push(member);
+
+                    if self.match_token(0, TokenKind::Comma) {
+                        self.i+=1;
+                    }
+                    else if ! self.match_token(0, TokenKind::CloseCurly) {
+                        self.error(format!("Expected `,` or `}}` but found `{}`", self.peek(0).literal));
+                        break;
+                    }
+                }
+
+                self.expect(TokenKind::CloseCurly, "`}`");
+            }
+            else {
+                self.error(format!("Expected a name, `*` or `{{` but found `{}`", self.peek(0).literal));
+            }
+        }
+
+        if self.match_token(0, TokenKind::AsKw) {
+            // Skip the AS
+
+            self.i+=1;
+
+            if statement.glob || ! statement.group.is_empty() {
+                self.error("Only a single item can be renamed with `as`".to_string());
+            }
+
+            statement.alias = Some(self.peek(0).literal.to_string());
+
+            self.expect(TokenKind::Identifier, "an alias");
+        }
+    }
+
     fn parse_namespace(&mut self) -> Namespace {
         let mut namespace: Namespace = Namespace::new();
 
@@ -54,412 +138,2191 @@ impl<'a> Parser<'a> {
 
         self.i+=1;
 
-        // Skip opening curly
+        // `namespace A.B` is the same as `namespace A { namespace B { } }`
+        namespace.path = self.namespace_path.clone();
+        namespace.path.extend(namespace.name.split('.').map(|part| part.to_string()));
 
-        self.i+=1;
+        if self.match_token(0, TokenKind::Semicolon) {
+            if ! self.namespace_path.is_empty() {
+                self.error("File-scoped namespaces can't be nested, use braces instead".to_string());
+            }
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            namespace.definitions.push(self.parse_definition());
+            namespace.file_scoped = true;
         }
+        else if ! self.match_token(0, TokenKind::OpenCurly) {
+            self.error(format!("Expected `{{` or `;` but found `{}`", self.peek(0).literal));
+        }
+
+        // Skip opening curly or the semicolon
 
         self.i+=1;
 
+        let outer = std::mem::replace(&mut self.namespace_path, namespace.path.clone());
+        self.scopes.push(vec![]);
+
+        // File-scoped namespaces run until the end of the file
+        while self.i < self.tokens.len() && (namespace.file_scoped || ! self.match_token(0, TokenKind::CloseCurly)) {
+            if self.match_token(0, TokenKind::NamespaceKw) {
+                namespace.namespaces.push(self.parse_namespace());
+            }
+            else if self.match_token(self.modifiers_length(0), TokenKind::UseKw) {
+                namespace.usestatements.push(self.parse_use_statement());
+            }
+            else if let Some(definition) = self.parse_namespace_definition() {
+                namespace.definitions.push(definition);
+            }
+        }
+
+        self.close_member_scope();
+        self.namespace_path = outer;
+
+        if ! namespace.file_scoped {
+            self.expect(TokenKind::CloseCurly, "`}`");
+        }
+
         return namespace;
     }
 
-    fn parse_variable_definition(&mut self) -> VariableDefinition {
-        let mut definition = VariableDefinition::new();
-        
-        if self.match_token(0, TokenKind::PubKw) {
-            definition.public = true;
+    // A definition directly inside a namespace or at the top of the file, `None` if there was nothing to parse
+    fn parse_namespace_definition(&mut self) -> Option<Definition> {
+        let start = self.i;
+        let location = self.peek(0).location;
+        let definition = self.parse_definition();
+
+        if self.i == start {
+            self.report(DiagnosticLevel::Error, format!("Expected a definition but found `{}`", self.peek(0).literal), location);
             self.i+=1;
+            return None;
         }
 
-        definition.dtype = self.peek(0).literal.to_string();
+        if matches!(definition, Definition::ConstructorDefinition(_)) {
+            self.report(DiagnosticLevel::Error, "Constructors can only be defined inside a class".to_string(), location);
+        }
 
-        self.i+=1;
+        self.declare_member(&definition);
 
-        definition.name = self.peek(0).literal.to_string();
+        return Some(definition);
+    }
 
-        self.i+=1;
+    fn parse_variable_definition(&mut self) -> VariableDefinition {
+        let mut definition = VariableDefinition::new();
+        let location = self.peek(0).location;
+
+        (definition.visibility, definition.is_static) = self.parse_modifiers();
+
+        let inferred = self.parse_mutability(&mut definition);
+        self.parse_binding(&mut definition, inferred);
 
         if self.match_token(0, TokenKind::Equals) {
             self.i+=1;//Skip Equals
             definition.value = self.parse_expression();
         }
+        else if definition.mutability == Mutability::Constant {
+            self.report(DiagnosticLevel::Error, "Constants need a value".to_string(), location);
+        }
+        else if definition.dtype.is_none() {
+            self.report(DiagnosticLevel::Error, "Variables without a type need a value to infer it from".to_string(), location);
+        }
 
         return definition;
     }
 
-    fn parse_expression(&mut self) -> Expression {
-        if self.is_operator(1) {
-            return Expression::BinaryOperation(self.parse_binary_operation());
+    // `let`, `var` and `const` can leave the type out, returns whether one of them was given
+    fn parse_mutability(&mut self, definition: &mut VariableDefinition) -> bool {
+        let inferred = self.is_mutability_keyword(0);
+        if self.match_token(0, TokenKind::LetKw) {
+            definition.mutability = Mutability::Immutable;
         }
-        else {
-            let mut term = Expression::Term(self.parse_term());
+        else if self.match_token(0, TokenKind::ConstKw) {
+            definition.mutability = Mutability::Constant;
+        }
+        if inferred {
             self.i+=1;
-            return term;
         }
+
+        return inferred;
     }
 
-    fn parse_binary_operation(&mut self) -> BinaryOperation {
-        let mut operation = BinaryOperation::new();
-    
-    
-        operation.left = Box::new(Expression::Term(self.parse_term()));
-    
-        self.i += 1;
+    // Parses the `Type name` or `(Type a, Type b)` part of a definition, types can be left out when `inferred`
+    fn parse_binding(&mut self, definition: &mut VariableDefinition, inferred: bool) {
+        if self.is_tuple_binding(0) || (inferred && self.match_token(0, TokenKind::OpenParen) && ! self.is_declaration(0)) {
+            // Skip the '('
 
-    
-        operation.op = self.peek(0).literal.to_string();
-    
-        self.i += 1;
-    
-        if self.is_operator(1) {
-            operation.right = Box::new(Expression::BinaryOperation(self.parse_binary_operation()));
-        } else {
-            operation.right = Box::new(Expression::Term(self.parse_term()));
             self.i+=1;
-        }
-    
-        operation
-    }
-    
 
-    fn parse_term(&mut self) -> Term {
-        if self.match_token(0, TokenKind::StringLit) {
-            return Term::StringLiteral(StringLiteral {value: self.peek(0).literal.to_string()})
-        }
-        else if self.match_token(0, TokenKind::IntLit) {
-            return Term::IntegerLiteral(IntegerLiteral {value: self.peek(0).literal.to_string()})
-        }
-        else if self.match_token(0, TokenKind::FloatLit) {
-            return Term::FloatLiteral(FloatLiteral {value: self.peek(0).literal.to_string()})
-        }
-        else if self.match_token(0, TokenKind::TrueKw) || self.match_token(0, TokenKind::FalseKw) {
-            return Term::BooleanLiteral(BooleanLiteral {value: self.peek(0).literal.to_string()})
-        }
-        else if self.match_token(0, TokenKind::Identifier) {
-            if self.match_token(1, TokenKind::OpenParen) {
-                return Term::FunctionCall(self.parse_function_call());
-            }
-            else {
-                return Term::VariableReference(VariableReference {value: self.peek(0).literal.to_string()});
+            let mut elements = vec![];
+
+            while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+                let mut element = VariableDefinition::new();
+                element.mutability = definition.mutability;
+                self.parse_binding(&mut element, inferred);
+                elements.push(element);
+
+                if self.match_token(0, TokenKind::Comma) {
+                    self.i+=1;
+                }
             }
+
+            // Skip the ')'
+
+            self.i+=1;
+
+            // The tuple's type is only known when all of its elements are
+            let dtypes: Option<Vec<TypeExpr>> = elements.iter().map(|element| element.dtype.clone()).collect();
+            definition.dtype = dtypes.map(TypeExpr::Tuple);
+            definition.binding = Binding::Tuple(elements);
         }
         else {
-            return Term::Null(Null {});
+            if self.is_declaration(0) || ! inferred {
+                definition.dtype = Some(self.parse_type());
+            }
+
+            definition.binding = Binding::Name(self.peek(0).literal.to_string());
+
+            self.i+=1;
         }
     }
 
-    fn parse_function_call(&mut self) -> FunctionCall {
-        let mut call = FunctionCall::new();
+    // Parses a type such as `int`, `int[]`, `List<string>`, `Foo?`, `(int, string)` or `(int, int) -> bool`
+    fn parse_type(&mut self) -> TypeExpr {
+        let mut dtype: TypeExpr;
 
-        call.name = self.peek(0).literal.to_string();
+        if self.match_token(0, TokenKind::OpenParen) {
+            // Skip the '('
 
-        self.i+=1;
+            self.i+=1;
 
-        // Skip OpenParen
+            let mut elements = vec![];
+            let mut trailing_comma = false;
 
-        self.i+=1;
+            while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+                elements.push(self.parse_type());
+                trailing_comma = false;
+
+                if self.match_token(0, TokenKind::Comma) {
+                    trailing_comma = true;
+                    self.i+=1;
+                }
+                else if ! self.match_token(0, TokenKind::CloseParen) {
+                    self.error(format!("Expected `,` or `)` in a type but found `{}`", self.peek(0).literal));
+                    break;
+                }
+            }
 
-        while ! self.match_token(0, TokenKind::CloseParen) {
-            call.arguements.push(self.parse_expression());
+            self.expect(TokenKind::CloseParen, "`)`");
+
+            if self.match_token(0, TokenKind::Arrow) {
+                // Skip the '->'
 
-            if self.match_token(0, TokenKind::Comma) {
                 self.i+=1;
+
+                dtype = TypeExpr::Function(FunctionType {parameters: elements, return_type: Box::new(self.parse_type())});
+            }
+            // `(int)` only groups, it isn't a one element tuple
+            else if elements.len() == 1 && ! trailing_comma {
+                dtype = elements.pop().unwrap();
+            }
+            else {
+                dtype = TypeExpr::Tuple(elements);
             }
         }
+        else if ! self.match_token(0, TokenKind::Identifier) {
+            // Leave the token for the caller, it's usually the `{` of a body
+            self.expect(TokenKind::Identifier, "a type");
+            return TypeExpr::Named(String::new());
+        }
+        else {
+            let name = self.peek(0).literal.to_string();
 
-        // Skip Closing Paren
+            self.i+=1;
 
-        self.i+=1;
-        
-        return call;
-    }
+            if self.match_token(0, TokenKind::LessThan) {
+                let arguments = self.parse_type_arguments();
 
-    fn parse_definition(&mut self) -> Definition {
-        if self.match_token(0, TokenKind::PubKw) {
-            if self.match_token(1, TokenKind::ClassKw) {
-                return Definition::ClassDefinition(self.parse_class_definition());
+                dtype = TypeExpr::Generic(GenericType {name, arguments});
             }
-            else if self.match_token(1, TokenKind::Identifier) && self.match_token(2, TokenKind::Identifier) {
-                if self.match_token(3, TokenKind::OpenParen) {
-                    return Definition::FunctionDefinition(self.parse_function_definition());
-                }
-                else {
-                    return Definition::VariableDefinition(self.parse_variable_definition());
-                }
+            else if name == "void" {
+                dtype = TypeExpr::Void;
+            }
+            else {
+                dtype = TypeExpr::Named(name);
             }
         }
-        else {
-            if self.match_token(0, TokenKind::ClassKw) {
-                return Definition::ClassDefinition(self.parse_class_definition());
+
+        // Suffixes apply left to right, `int?[]` is an array of nullable ints
+        loop {
+            if self.match_token(0, TokenKind::OpenBracket) && self.match_token(1, TokenKind::CloseBarcket) {
+                self.i+=2;
+                dtype = TypeExpr::Array(Box::new(dtype));
             }
-            else if self.match_token(0, TokenKind::Identifier) && self.match_token(1, TokenKind::Identifier) {
-                if self.match_token(2, TokenKind::OpenParen) {
-                    return Definition::FunctionDefinition(self.parse_function_definition());
-                }
-                else {
-                    return Definition::VariableDefinition(self.parse_variable_definition());
-                }
+            else if self.match_token(0, TokenKind::Question) {
+                self.i+=1;
+                dtype = TypeExpr::Nullable(Box::new(dtype));
+            }
+            else {
+                break;
             }
         }
-        return  Definition::FunctionDefinition(FunctionDefinition::new());
-    }
 
+        return dtype;
+    }
 
-    fn parse_function_definition(&mut self) -> FunctionDefinition {
-        let mut definition = FunctionDefinition::new();
+    // `<int, List<string>>` after a generic type or function name
+    fn parse_type_arguments(&mut self) -> Vec<TypeExpr> {
+        // Skip the '<'
 
-        if self.match_token(0, TokenKind::PubKw) {
-            definition.public = true;
-            self.i+=1;
-        }
+        self.i+=1;
 
-        definition.ftype = self.peek(0).literal.to_string();
+        let mut arguments = vec![];
 
-        self.i+=1;
+        loop {
+            arguments.push(self.parse_type());
 
-        definition.name = self.peek(0).literal.to_string();
+            // A nested type argument already consumed the first half of a `>>`
+            if self.split_gt {
+                self.split_gt = false;
+                self.i+=1;
+                break;
+            }
 
-        self.i+=1;
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+            else if self.match_token(0, TokenKind::GreaterThan) {
+                self.i+=1;
+                break;
+            }
+            else if self.match_token(0, TokenKind::ShiftRight) {
+                // `List<List<int>>`, the second `>` closes the enclosing type
+                self.split_gt = true;
+                break;
+            }
+            else {
+                self.error(format!("Expected `,` or `>` in a type but found `{}`", self.peek(0).literal));
+                break;
+            }
+        }
 
-        // Skip Open Paren
+        return arguments;
+    }
 
-        self.i+=1;
+    // `<T, U : Comparable + Hashable>` after a class, interface or function name
+    fn parse_type_parameters(&mut self) -> Vec<TypeParameter> {
+        let mut parameters = vec![];
 
-        while ! self.match_token(0, TokenKind::CloseParen) {
-            definition.arguements.push(self.parse_variable_definition());
+        if ! self.match_token(0, TokenKind::LessThan) {
+            return parameters;
         }
 
+        // Skip the '<'
+
         self.i+=1;
 
-        // Skip Opening Curly
+        loop {
+            let mut parameter = TypeParameter::new();
 
-        self.i+=1;
+            if ! self.match_token(0, TokenKind::Identifier) {
+                self.error(format!("Expected a type parameter but found `{}`", self.peek(0).literal));
+                break;
+            }
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            definition.statements.push(self.parse_statement());
-        }
+            parameter.name = self.peek(0).literal.to_string();
 
-        self.i+=1;
+            self.i+=1;
 
-        return definition;
-    }
+            if self.match_token(0, TokenKind::Colon) {
+                // Skip the ':'
 
-    fn parse_statement(&mut self) -> Statement {
-        let mut statment: Statement;
-        if self.match_token(0, TokenKind::Identifier) {
-            if self.match_token(1, TokenKind::OpenParen) {
-                statment = Statement::FunctionCall(self.parse_function_call());
+                self.i+=1;
+
+                loop {
+                    parameter.constraints.push(self.parse_type());
+
+                    if ! self.match_token(0, TokenKind::Add) {
+                        break;
+                    }
+
+                    self.i+=1;
+                }
             }
-            else if self.match_token(1, TokenKind::Identifier) {
-                statment = Statement::VariableDefinition(self.parse_variable_definition());
+
+            parameters.push(parameter);
+
+            // `T : IComparable<T>>`, the constraint used the first half of the `>>`
+            if self.split_gt {
+                self.split_gt = false;
+                self.i+=1;
+                break;
             }
-            else if self.match_token(1, TokenKind::Equals) {
-                statment = Statement::VariableRedefinition(self.parse_variable_redefinition());
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
             }
             else {
-                statment = Statement::Null(Null {});
+                self.expect(TokenKind::GreaterThan, "`>`");
+                break;
             }
         }
-        else if self.match_token(0, TokenKind::IfKw) {
-            statment = Statement::IfStatement(self.parse_if_statement());
-        }
-        else if self.match_token(0, TokenKind::ElifKw) {
-            statment = Statement::ElifStatement(self.parse_elif_statement());
-        }
-        else if self.match_token(0, TokenKind::ElseKw) {
-            statment = Statement::ElseStatement(self.parse_else_statement());
-        } 
-        else if self.match_token(0, TokenKind::WhileKw) {
-            statment = Statement::WhileStatement(self.parse_while_statement());
-        }
-        else {
-            statment = Statement::Null(Null {});
-        }
 
-        //Skip Semicolon
-        self.i+=1;
-        
+        return parameters;
+    }
 
-        return statment;
+    fn parse_expression(&mut self) -> Expression {
+        return self.parse_binary_operation(0);
     }
 
-    fn parse_if_statement(&mut self) -> IfStatement {
-        let mut statement = IfStatement::new();
+    // Precedence climbing, operators that bind tighter than `min_precedence` are folded into the right hand side
+    fn parse_binary_operation(&mut self, min_precedence: u8) -> Expression {
+        let mut left = if self.is_range_operator(0) {
+            // Ranges with an open start, eg. `..10`
+            Expression::Null(Null {})
+        } else {
+            self.parse_unary_expression()
+        };
 
-        //Skip the IF
+        while let Some(op) = Op::from_binary_token(&self.peek(0).kind) {
+            if op.precedence() < min_precedence {
+                break;
+            }
 
-        self.i+=1;
+            // Skip the operator
 
-        //Skip the '('
+            self.i+=1;
 
-        self.i+=1;
+            if op.is_range() {
+                left = Expression::Range(self.parse_range(left, op));
 
-        statement.condition = self.parse_expression();
+                if self.is_range_operator(0) {
+                    self.error("Ranges can't be chained, `a..b..c` has no meaning".to_string());
+                }
+                continue;
+            }
 
-        //Skip the ')'
-        self.i+=1;
+            let next_precedence = if op.is_right_associative() { op.precedence() } else { op.precedence() + 1 };
 
-        //SKip the '{'
-        self.i+=1;
+            let mut operation = BinaryOperation::new();
+            operation.left = Box::new(left);
+            operation.op = op.to_str().to_string();
+            operation.right = Box::new(self.parse_binary_operation(next_precedence));
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            statement.statements.push(self.parse_statement());
+            left = Expression::BinaryOperation(operation);
         }
 
-        return statement;
+        return left;
     }
 
-    fn parse_while_statement(&mut self) -> WhileStatement {
-        let mut statement = WhileStatement::new();
-
-        //Skip the IF
+    fn parse_range(&mut self, start: Expression, op: Op) -> Range {
+        let mut range = Range::new();
+        range.start = Box::new(start);
+        range.inclusive = op == Op::RangeInclusive;
 
-        self.i+=1;
+        if self.is_expression_start(0) {
+            range.end = Box::new(self.parse_binary_operation(op.precedence() + 1));
+        }
+        else if range.inclusive {
+            self.error("Inclusive ranges need an end, eg. `start..=end`".to_string());
+        }
 
-        //Skip the '('
+        return range;
+    }
 
-        self.i+=1;
+    fn parse_unary_expression(&mut self) -> Expression {
+        // Negative literals are kept as literals so their range can be checked
+        if self.is_negative_literal(0) {
+            return self.parse_postfix_expression();
+        }
 
-        statement.condition = self.parse_expression();
+        if let Some(op) = Op::from_unary_token(&self.peek(0).kind) {
+            // Skip the operator
 
-        //Skip the ')'
-        self.i+=1;
+            self.i+=1;
 
-        //SKip the '{'
-        self.i+=1;
+            let mut operation = UnaryOperation::new();
+            operation.op = op.to_str().to_string();
+            operation.operand = Box::new(self.parse_unary_expression());
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            statement.statements.push(self.parse_statement());
+            return Expression::UnaryOperation(operation);
         }
 
-        return statement;
+        return self.parse_postfix_expression();
     }
 
-    fn parse_elif_statement(&mut self) -> ElifStatement {
-        let mut statement = ElifStatement::new();
+    fn parse_postfix_expression(&mut self) -> Expression {
+        let mut expression = self.parse_primary_expression();
 
-        //Skip the ELIF
+        loop {
+            if self.match_token(0, TokenKind::QuestionDot) || self.match_token(0, TokenKind::Dot) {
+                let null_safe = self.match_token(0, TokenKind::QuestionDot);
 
-        self.i+=1;
+                // Skip the '.' or '?.'
 
-        //Skip the '('
+                self.i+=1;
 
-        self.i+=1;
+                let path = self.peek(0).literal.to_string();
+                expression = self.parse_member_access(expression, &path, null_safe);
+            }
+            else if self.match_token(0, TokenKind::OpenBracket) {
+                //Skip the '['
 
-        statement.condition = self.parse_expression();
+                self.i+=1;
 
-        //Skip the ')'
-        self.i+=1;
+                let index = self.parse_expression();
 
-        //SKip the '{'
-        self.i+=1;
+                self.expect(TokenKind::CloseBarcket, "`]`");
+
+                expression = Expression::Index(Index {object: Box::new(expression), index: Box::new(index)});
+            }
+            else if self.match_token(0, TokenKind::OpenParen) {
+                let arguements = self.parse_arguements();
+                expression = Expression::Call(Call {callee: Box::new(expression), arguements});
+            }
+            else if self.match_token(0, TokenKind::Increment) || self.match_token(0, TokenKind::Decrement) {
+                let mut operation = UnaryOperation::new();
+                operation.op = self.peek(0).literal.to_string();
+                operation.operand = Box::new(expression);
+                operation.postfix = true;
+
+                self.i+=1;
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            statement.statements.push(self.parse_statement());
+                expression = Expression::UnaryOperation(operation);
+            }
+            else {
+                break;
+            }
         }
-        
-        return statement;
-    }
 
-    fn parse_else_statement(&mut self) -> ElseStatement {
-        let mut statement = ElseStatement::new();
+        return expression;
+    }
 
-        //Skip the IF
+    fn parse_member_access(&mut self, object: Expression, path: &str, null_safe: bool) -> Expression {
+        // Dotted names are a single token, so `a?.b.c` is split back up into `(a?.b).c`
+        let mut members = path.split('.');
+        let last = members.next_back().unwrap_or_default().to_string();
 
-        self.i+=1;
+        let mut object = object;
+        let mut null_safe = null_safe;
+        for member in members {
+            object = Expression::MemberAccess(MemberAccess {object: Box::new(object), member: member.to_string(), null_safe});
+            null_safe = false;
+        }
 
-        //SKip the '{'
         self.i+=1;
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            statement.statements.push(self.parse_statement());
+        if self.match_token(0, TokenKind::OpenParen) || self.generic_call_length(0) > 0 {
+            let type_arguments = if self.match_token(0, TokenKind::LessThan) { self.parse_type_arguments() } else { vec![] };
+            let arguements = self.parse_arguements();
+            return Expression::MethodCall(MethodCall {object: Box::new(object), name: last, type_arguments, arguements, null_safe});
         }
-        
-        return statement;
+
+        return Expression::MemberAccess(MemberAccess {object: Box::new(object), member: last, null_safe});
     }
 
-    fn parse_variable_redefinition(&mut self) -> VariableRedefinition {
-        let mut redefinition = VariableRedefinition::new();
+    fn parse_primary_expression(&mut self) -> Expression {
+        if self.match_token(0, TokenKind::OpenParen) {
+            //Skip the '('
 
-        redefinition.name = self.peek(0).literal.to_string();
+            self.i+=1;
+
+            let mut tuple = TupleLiteral::new();
+
+            while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+                tuple.values.push(self.parse_expression());
+
+                // A single value without a trailing comma is just a parenthesized expression
+                if ! self.match_token(0, TokenKind::Comma) && tuple.values.len() == 1 {
+                    let expression = tuple.values.pop().unwrap();
+
+                    self.expect(TokenKind::CloseParen, "`)`");
+
+                    return expression;
+                }
+
+                if self.match_token(0, TokenKind::Comma) {
+                    self.i+=1;
+                }
+                else if ! self.match_token(0, TokenKind::CloseParen) {
+                    break;
+                }
+            }
+
+            self.expect(TokenKind::CloseParen, "`)`");
+
+            return Expression::Term(Term::TupleLiteral(tuple));
+        }
+        else if self.match_token(0, TokenKind::NewKw) {
+            return Expression::New(self.parse_new_expression());
+        }
+        else if self.match_token(0, TokenKind::ThisKw) {
+            self.i+=1;
+
+            return Expression::This(This {});
+        }
+        // `this.x` lexes as one dotted name, split it back up into members of `this`
+        else if self.match_token(0, TokenKind::Identifier) && self.peek(0).literal.starts_with("this.") {
+            let path = self.peek(0).literal["this.".len()..].to_string();
+
+            return self.parse_member_access(Expression::This(This {}), &path, false);
+        }
+        else if self.is_struct_literal(0) {
+            return Expression::Term(Term::StructLiteral(self.parse_struct_literal()));
+        }
+
+        return Expression::Term(self.parse_term());
+    }
+
+    fn parse_term(&mut self) -> Term {
+        let term: Term;
+        if self.match_token(0, TokenKind::StringLit) {
+            term = Term::StringLiteral(StringLiteral {value: self.peek(0).literal.to_string()});
+        }
+        else if self.peek(0).is_int_literal() {
+            term = Term::IntegerLiteral(self.parse_integer_literal(false));
+        }
+        else if self.match_token(0, TokenKind::FloatLit) {
+            term = Term::FloatLiteral(self.parse_float_literal(false));
+        }
+        else if self.is_negative_literal(0) {
+            // Skip the '-', `-128i8` is range checked as a whole
+            self.i+=1;
+
+            if self.match_token(0, TokenKind::FloatLit) {
+                term = Term::FloatLiteral(self.parse_float_literal(true));
+            }
+            else {
+                term = Term::IntegerLiteral(self.parse_integer_literal(true));
+            }
+        }
+        else if self.match_token(0, TokenKind::TrueKw) || self.match_token(0, TokenKind::FalseKw) {
+            term = Term::BooleanLiteral(BooleanLiteral {value: self.peek(0).literal.to_string()});
+        }
+        else if self.match_token(0, TokenKind::NullKw) {
+            term = Term::NullLiteral(NullLiteral {});
+        }
+        else if self.match_token(0, TokenKind::Identifier) {
+            if self.match_token(1, TokenKind::OpenParen) || self.generic_call_length(1) > 0 {
+                // Function calls skip their own tokens
+                return Term::FunctionCall(self.parse_function_call());
+            }
+            else {
+                term = Term::VariableReference(VariableReference {value: self.peek(0).literal.to_string()});
+            }
+        }
+        else {
+            return Term::Null(Null {});
+        }
 
         self.i+=1;
 
-        //Skip Equals
+        return term;
+    }
+
+    fn parse_integer_literal(&mut self, negative: bool) -> IntegerLiteral {
+        let token = self.peek(0);
+        let sign = if negative { "-" } else { "" };
+        let suffix = token.numeric_suffix().and_then(NumericSuffix::from_literal);
+
+        // Unsuffixed integers have to fit in the widest signed type
+        let (min, max) = suffix.and_then(|suffix| suffix.int_range()).unwrap_or((i64::MIN as i128, i64::MAX as i128));
+        let type_name = suffix.map_or("i64", |suffix| suffix.to_str());
+
+        match token.to_int_literal().map(|value| if negative { -value } else { value }) {
+            Some(value) if value >= min && value <= max => {
+                return IntegerLiteral {value, suffix};
+            }
+            _ => {
+                self.error(format!("Integer literal `{}{}` does not fit in {}", sign, token.literal, type_name));
+                return IntegerLiteral {value: 0, suffix};
+            }
+        }
+    }
+
+    fn parse_float_literal(&mut self, negative: bool) -> FloatLiteral {
+        let token = self.peek(0);
+        let suffix = token.numeric_suffix().and_then(NumericSuffix::from_literal);
+        let value = if negative { -token.to_float_literal() } else { token.to_float_literal() };
+        let sign = if negative { "-" } else { "" };
+
+        let overflows = match suffix {
+            Some(NumericSuffix::F32) => value.abs() > f32::MAX as f64,
+            _ => value.is_infinite(),
+        };
+
+        if overflows {
+            let type_name = suffix.map_or("f64", |suffix| suffix.to_str());
+            self.error(format!("Float literal `{}{}` does not fit in {}", sign, token.literal, type_name));
+        }
+
+        return FloatLiteral {value, suffix};
+    }
+
+    fn parse_function_call(&mut self) -> FunctionCall {
+        let mut call = FunctionCall::new();
+
+        call.name = self.peek(0).literal.to_string();
 
         self.i+=1;
 
-        redefinition.value = self.parse_expression();
+        if self.match_token(0, TokenKind::LessThan) {
+            call.type_arguments = self.parse_type_arguments();
+        }
 
-        return redefinition;
+        call.arguements = self.parse_arguements();
+        
+        return call;
     }
 
-    fn parse_class_definition(&mut self) -> ClassDefinition {
-        let mut class = ClassDefinition::new();
+    fn parse_struct_literal(&mut self) -> StructLiteral {
+        let mut literal = StructLiteral::new();
+
+        literal.name = self.peek(0).literal.to_string();
+
+        // Skip the name and the '{'
+
+        self.i+=2;
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            let name = self.peek(0).literal.to_string();
+
+            if ! self.match_token(0, TokenKind::Identifier) {
+                self.error(format!("Expected a field name but found `{}`", name));
+            }
 
-        if self.match_token(0, TokenKind::PubKw) {
-            class.public = true;
             self.i+=1;
+
+            self.expect(TokenKind::Colon, "`:`");
+
+            if literal.fields.iter().any(|field| field.name == name) {
+                self.error(format!("Field `{}` is initialized more than once", name));
+            }
+
+            let value = self.parse_expression();
+            literal.fields.push(FieldInitializer {name, value});
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+            else if ! self.match_token(0, TokenKind::CloseCurly) {
+                break;
+            }
         }
 
-        self.i+=1;
+        self.expect(TokenKind::CloseCurly, "`,` or `}`");
 
-        class.name = self.peek(0).literal.to_string();
+        return literal;
+    }
+
+    fn parse_new_expression(&mut self) -> NewExpression {
+        let mut expression = NewExpression::new();
+
+        //Skip the NEW
 
         self.i+=1;
 
-        // SKip open curly
+        expression.class = self.parse_type();
+
+        expression.arguements = self.parse_arguements();
+
+        return expression;
+    }
+
+    // Parses a parenthesized, comma separated list of arguements
+    fn parse_arguements(&mut self) -> Vec<Expression> {
+        let mut arguements = vec![];
+
+        // Skip OpenParen
 
         self.i+=1;
 
-        while ! self.match_token(0, TokenKind::CloseCurly) {
-            class.definitions.push(self.parse_definition());
+        while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+            arguements.push(self.parse_expression());
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+            else if ! self.match_token(0, TokenKind::CloseParen) {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::CloseParen, "`)`");
+
+        return arguements;
+    }
+
+    fn parse_definition(&mut self) -> Definition {
+        let offset = self.modifiers_length(0);
+
+        if self.match_token(offset, TokenKind::ClassKw) {
+            return Definition::ClassDefinition(self.parse_class_definition());
+        }
+        else if self.match_token(offset, TokenKind::InterfaceKw) {
+            return Definition::InterfaceDefinition(self.parse_interface_definition());
+        }
+        else if self.match_token(offset, TokenKind::EnumKw) {
+            return Definition::EnumDefinition(self.parse_enum_definition());
+        }
+        else if self.match_token(offset, TokenKind::StructKw) {
+            return Definition::StructDefinition(self.parse_struct_definition());
+        }
+        else if self.match_token(offset, TokenKind::NewKw) && self.match_token(offset + 1, TokenKind::OpenParen) {
+            return Definition::ConstructorDefinition(self.parse_constructor_definition());
+        }
+        else if self.match_token(offset, TokenKind::FnKw) || self.is_function_declaration(offset) {
+            return Definition::FunctionDefinition(self.parse_function_definition());
+        }
+        else if self.is_declaration(offset) || self.is_mutability_keyword(offset) {
+            let definition = self.parse_variable_definition();
+
+            //Skip Semicolon
+            if self.match_token(0, TokenKind::Semicolon) {
+                self.i+=1;
+            }
+
+            return Definition::VariableDefinition(definition);
+        }
+        return  Definition::FunctionDefinition(FunctionDefinition::new());
+    }
+
+
+    fn parse_function_definition(&mut self) -> FunctionDefinition {
+        let mut definition = FunctionDefinition::new();
+
+        (definition.visibility, definition.is_static) = self.parse_modifiers();
+
+        self.scopes.push(vec![]);
+
+        let signature = self.parse_method_signature();
+        for arguement in &signature.arguements {
+            self.declare(&arguement.binding, arguement.mutability);
+        }
+
+        definition.ftype = signature.ftype;
+        definition.name = signature.name;
+        definition.type_parameters = signature.type_parameters;
+        definition.arguements = signature.arguements;
+
+        definition.body = self.parse_block();
+
+        self.scopes.pop();
+
+        return definition;
+    }
+
+    fn parse_constructor_definition(&mut self) -> ConstructorDefinition {
+        let mut definition = ConstructorDefinition::new();
+
+        definition.visibility = self.parse_visibility();
+
+        // Skip the NEW and the '('
+
+        self.i+=2;
+
+        self.scopes.push(vec![]);
+
+        while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+            let arguement = self.parse_variable_definition();
+            self.declare(&arguement.binding, arguement.mutability);
+            definition.arguements.push(arguement);
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
         }
 
         self.i+=1;
 
-        return class;
+        definition.body = self.parse_block();
+
+        self.scopes.pop();
+
+        return definition;
     }
 
-    fn match_token(&mut self, offset: isize, t: TokenKind) -> bool {
-        return self.peek(offset).kind == t;
+    // Leading `pub`, `private`, `protected`, `internal` and `static` in any order, private unless stated otherwise
+    fn parse_modifiers(&mut self) -> (Visibility, bool) {
+        let mut visibility = None;
+        let mut is_static = false;
+
+        loop {
+            let modifier = match self.peek(0).kind {
+                TokenKind::PubKw => Some(Visibility::Public),
+                TokenKind::PrivateKw => Some(Visibility::Private),
+                TokenKind::ProtectedKw => Some(Visibility::Protected),
+                TokenKind::InternalKw => Some(Visibility::Internal),
+                TokenKind::StaticKw => None,
+                _ => break,
+            };
+
+            if let Some(modifier) = modifier {
+                if visibility.is_some() {
+                    self.error(format!("Visibility was already given, `{}` conflicts with it", self.peek(0).literal));
+                }
+                visibility = Some(modifier);
+            }
+            else {
+                if is_static {
+                    self.error("`static` is given more than once".to_string());
+                }
+                is_static = true;
+            }
+
+            self.i+=1;
+        }
+
+        return (visibility.unwrap_or(Visibility::Private), is_static);
     }
 
-    fn peek(&self, offset: isize) -> Token {
-        let index = self.i as isize + offset;
-        if index >= 0 && index < self.tokens.len() as isize {
-            self.tokens[index as usize].clone()
-        } else {
-            Token {kind: TokenKind::Whitespace, location: SourceLoc {line: 0, start: 0, end: 0}, literal: ""}
+    // Modifiers for definitions that can't be `static`, such as classes and constructors
+    fn parse_visibility(&mut self) -> Visibility {
+        let location = self.peek(0).location;
+        let (visibility, is_static) = self.parse_modifiers();
+
+        if is_static {
+            self.report(DiagnosticLevel::Error, "Only fields and methods can be `static`".to_string(), location);
+        }
+
+        return visibility;
+    }
+
+    // Everything in a function definition up to its body, `fn name(Type a) -> Type` or `Type name(Type a)`
+    fn parse_method_signature(&mut self) -> MethodSignature {
+        let mut signature = MethodSignature::new();
+
+        // `fn name(...) -> Type`, the return type comes after the parameters
+        let declared_with_fn = self.match_token(0, TokenKind::FnKw);
+        if declared_with_fn {
+            self.i+=1;
+        }
+        else {
+            signature.ftype = self.parse_type();
+        }
+
+        if ! self.match_token(0, TokenKind::Identifier) {
+            self.error(format!("Expected a function name but found `{}`", self.peek(0).literal));
+        }
+
+        signature.name = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        signature.type_parameters = self.parse_type_parameters();
+
+        self.expect(TokenKind::OpenParen, "`(`");
+
+        while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+            signature.arguements.push(self.parse_variable_definition());
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+        }
+
+        self.i+=1;
+
+        if self.match_token(0, TokenKind::Arrow) {
+            // Skip the '->'
+
+            self.i+=1;
+
+            if ! declared_with_fn {
+                self.error(format!("`{}` already has a return type before its name, remove the `->`", signature.name));
+            }
+
+            signature.ftype = self.parse_type();
         }
+
+        return signature;
+    }
+
+    fn parse_statement(&mut self) -> Statement {
+        let statement = self.parse_unterminated_statement();
+
+        let simple = match &statement {
+            Statement::VariableDefinition(_) | Statement::VariableRedefinition(_) | Statement::Assignment(_)
+                | Statement::Expression(_) | Statement::FunctionCall(_)
+                | Statement::Return(_) | Statement::Throw(_) | Statement::Break(_) | Statement::Continue(_) => true,
+            Statement::Defer(defer) => ! matches!(*defer.body, Statement::Block(_)),
+            _ => false,
+        };
+
+        // Simple statements end with a semicolon, anything else may still have a stray one after it
+        if simple {
+            self.expect(TokenKind::Semicolon, "`;`");
+        }
+        else if self.match_token(0, TokenKind::Semicolon) {
+            self.i+=1;
+        }
+
+        return statement;
     }
 
-    fn is_operator(&mut self, offset: isize) -> bool {
-        // Create a vector of the operator tokens you want to check
-        let operators = vec![
-            TokenKind::Add,
-            TokenKind::Subtract,
-            TokenKind::Multiply,
-            TokenKind::Divide,
-            TokenKind::Modulus,
-            TokenKind::EqualsEquals,
-            TokenKind::NotEqual,
-            TokenKind::LessThan,
-            TokenKind::GreaterThan,
-            TokenKind::LessEqual,
-            TokenKind::GreaterEq,
-        ];
+    // A statement without its `;`, match arms end theirs with a `,` instead
+    fn parse_unterminated_statement(&mut self) -> Statement {
+        let statment: Statement;
+
+        // Loop labels, eg. `outer: while (...) { }`
+        let mut label = None;
+        if self.match_token(0, TokenKind::Identifier) && self.match_token(1, TokenKind::Colon) {
+            label = Some(self.peek(0).literal.to_string());
+            self.i+=2;
+
+            if ! matches!(self.peek(0).kind, TokenKind::WhileKw | TokenKind::ForKw | TokenKind::DoKw | TokenKind::LoopKw) {
+                self.error(format!("Label `{}` must be followed by a loop", label.as_ref().unwrap()));
+            }
+        }
+
+        if self.is_declaration(0) || self.is_mutability_keyword(0) {
+            let definition = self.parse_variable_definition();
+            self.declare(&definition.binding, definition.mutability);
+            statment = Statement::VariableDefinition(definition);
+        }
+        else if self.is_variable_redefinition(0) {
+            statment = Statement::VariableRedefinition(self.parse_variable_redefinition());
+        }
+        else if self.match_token(0, TokenKind::OpenCurly) {
+            statment = Statement::Block(self.parse_block());
+        }
+        else if self.match_token(0, TokenKind::IfKw) {
+            statment = Statement::IfStatement(self.parse_if_statement());
+        }
+        else if self.match_token(0, TokenKind::ElifKw) || self.match_token(0, TokenKind::ElseKw) {
+            // Branches are parsed as part of their `if`, so this one is missing it
+            self.error(format!("`{}` without a matching `if`", self.peek(0).literal));
+
+            if self.match_token(0, TokenKind::ElifKw) {
+                self.parse_elif_statement();
+            }
+            else {
+                self.parse_else_statement();
+            }
+            statment = Statement::Null(Null {});
+        }
+        else if self.match_token(0, TokenKind::TryKw) {
+            statment = Statement::Try(self.parse_try_statement());
+        }
+        else if self.match_token(0, TokenKind::CatchKw) || self.match_token(0, TokenKind::FinallyKw) {
+            self.error(format!("`{}` without a matching `try`", self.peek(0).literal));
+
+            if self.match_token(0, TokenKind::CatchKw) {
+                self.parse_catch_clause();
+            }
+            else {
+                self.i+=1;
+                self.parse_block();
+            }
+            statment = Statement::Null(Null {});
+        }
+        else if self.match_token(0, TokenKind::DeferKw) {
+            //Skip the DEFER
+            self.i+=1;
+
+            let body = if self.match_token(0, TokenKind::OpenCurly) {
+                Statement::Block(self.parse_block())
+            } else {
+                self.parse_expression_statement()
+            };
+            statment = Statement::Defer(DeferStatement {body: Box::new(body)});
+        }
+        else if self.match_token(0, TokenKind::ThrowKw) {
+            let location = self.peek(0).location;
+
+            //Skip the THROW
+            self.i+=1;
 
-        // Check if the current token matches any of the operators
-        for operator in operators {
-            if self.match_token(offset, operator) {
-                return true;
+            if self.is_expression_start(0) {
+                statment = Statement::Throw(ThrowStatement {value: self.parse_expression()});
+            }
+            else {
+                self.report(DiagnosticLevel::Error, "`throw` needs a value".to_string(), location);
+                statment = Statement::Throw(ThrowStatement {value: Expression::Null(Null {})});
+            }
+        }
+        else if self.match_token(0, TokenKind::WhileKw) {
+            statment = Statement::WhileStatement(self.parse_while_statement(label));
+        }
+        else if self.match_token(0, TokenKind::ForKw) {
+            let length = self.loop_variable_length(2);
+            if length > 0 && self.match_token(2 + length, TokenKind::InKw) {
+                statment = Statement::ForEachStatement(self.parse_for_each_statement(label));
+            }
+            else {
+                statment = Statement::ForStatement(self.parse_for_statement(label));
             }
         }
-        false
+        else if self.match_token(0, TokenKind::DoKw) {
+            statment = Statement::DoWhileStatement(self.parse_do_while_statement(label));
+        }
+        else if self.match_token(0, TokenKind::LoopKw) {
+            let mut statement = LoopStatement::new();
+            statement.label = label;
+
+            //Skip the LOOP
+            self.i+=1;
+
+            statement.body = self.parse_loop_body(&statement.label);
+            statment = Statement::LoopStatement(statement);
+        }
+        else if self.match_token(0, TokenKind::BreakKw) {
+            statment = Statement::Break(BreakStatement {label: self.parse_loop_jump("break")});
+        }
+        else if self.match_token(0, TokenKind::ContinueKw) {
+            statment = Statement::Continue(ContinueStatement {label: self.parse_loop_jump("continue")});
+        }
+        else if self.match_token(0, TokenKind::MatchKw) {
+            statment = Statement::Match(self.parse_match_statement());
+        }
+        else if self.match_token(0, TokenKind::ReturnKw) {
+            statment = Statement::Return(self.parse_return_statement());
+        }
+        else if self.is_expression_start(0) {
+            statment = self.parse_expression_statement();
+        }
+        else {
+            statment = Statement::Null(Null {});
+
+            // A lone `;` is an empty statement, anything else can't start one
+            if ! self.match_token(0, TokenKind::Semicolon) {
+                self.error(format!("Expected a statement but found `{}`", self.peek(0).literal));
+                self.i+=1;
+            }
+        }
+
+        return statment;
+    }
+
+    fn parse_expression_statement(&mut self) -> Statement {
+        let location = self.peek(0).location;
+        let expression = self.parse_expression();
+
+        if self.is_assignment_operator(0) {
+            if ! matches!(expression, Expression::Index(_) | Expression::MemberAccess(_) | Expression::Term(Term::VariableReference(_))) {
+                self.report(DiagnosticLevel::Error, "Only names, members and indexes can be assigned to".to_string(), location.clone());
+            }
+
+            let op = self.peek(0).literal.to_string();
+
+            // Skip the operator
+
+            self.i+=1;
+
+            self.check_target(&expression, location.clone());
+
+            let value = self.parse_expression();
+
+            return Statement::Assignment(Assignment {target: expression, op, value});
+        }
+
+        if let Expression::UnaryOperation(operation) = &expression {
+            if operation.op == "++" || operation.op == "--" {
+                self.check_target(&operation.operand, location.clone());
+            }
+        }
+
+        // Plain calls keep their own statement
+        if let Expression::Term(Term::FunctionCall(call)) = expression {
+            return Statement::FunctionCall(call);
+        }
+
+        if ! expression.has_side_effects() {
+            self.report(DiagnosticLevel::Warning, "Expression statement has no effect".to_string(), location);
+        }
+
+        return Statement::Expression(expression);
+    }
+
+    fn parse_if_statement(&mut self) -> IfStatement {
+        let mut statement = IfStatement::new();
+
+        //Skip the IF
+
+        self.i+=1;
+
+        //Skip the '('
+
+        self.i+=1;
+
+        statement.condition = self.parse_expression();
+
+        //Skip the ')'
+        self.i+=1;
+
+        statement.body = self.parse_block();
+
+        while self.match_token(0, TokenKind::ElifKw) {
+            statement.elifs.push(self.parse_elif_statement());
+        }
+
+        if self.match_token(0, TokenKind::ElseKw) {
+            statement.else_statement = Some(self.parse_else_statement());
+        }
+
+        return statement;
+    }
+
+    fn parse_while_statement(&mut self, label: Option<String>) -> WhileStatement {
+        let mut statement = WhileStatement::new();
+        statement.label = label;
+
+        //Skip the IF
+
+        self.i+=1;
+
+        //Skip the '('
+
+        self.i+=1;
+
+        statement.condition = self.parse_expression();
+
+        //Skip the ')'
+        self.i+=1;
+
+        statement.body = self.parse_loop_body(&statement.label);
+
+        return statement;
+    }
+
+    fn parse_elif_statement(&mut self) -> ElifStatement {
+        let mut statement = ElifStatement::new();
+
+        //Skip the ELIF
+
+        self.i+=1;
+
+        //Skip the '('
+
+        self.i+=1;
+
+        statement.condition = self.parse_expression();
+
+        //Skip the ')'
+        self.i+=1;
+
+        statement.body = self.parse_block();
+        
+        return statement;
+    }
+
+    fn parse_else_statement(&mut self) -> ElseStatement {
+        let mut statement = ElseStatement::new();
+
+        //Skip the ELSE
+
+        self.i+=1;
+
+        statement.body = self.parse_block();
+        
+        return statement;
+    }
+
+    fn parse_for_statement(&mut self, label: Option<String>) -> ForStatement {
+        let mut statement = ForStatement::new();
+        statement.label = label;
+
+        //Skip the FOR
+
+        self.i+=1;
+
+        self.expect(TokenKind::OpenParen, "`(`");
+
+        // The loop variable is only visible inside the loop
+        self.scopes.push(vec![]);
+
+        // Skips its own semicolon
+        statement.initializer = Box::new(self.parse_statement());
+
+        if ! self.match_token(0, TokenKind::Semicolon) {
+            statement.condition = self.parse_expression();
+        }
+
+        self.expect(TokenKind::Semicolon, "`;`");
+
+        if self.is_variable_redefinition(0) {
+            statement.update = Box::new(Statement::VariableRedefinition(self.parse_variable_redefinition()));
+        }
+        else if self.is_expression_start(0) {
+            statement.update = Box::new(self.parse_expression_statement());
+        }
+
+        self.expect(TokenKind::CloseParen, "`)`");
+
+        statement.body = self.parse_loop_body(&statement.label);
+
+        self.scopes.pop();
+
+        return statement;
+    }
+
+    fn parse_for_each_statement(&mut self, label: Option<String>) -> ForEachStatement {
+        let mut statement = ForEachStatement::new();
+        statement.label = label;
+
+        //Skip the FOR
+
+        self.i+=1;
+
+        self.expect(TokenKind::OpenParen, "`(`");
+
+        // The variable gets its value from the iterable, so `let x` doesn't need a type or an initializer
+        let inferred = self.parse_mutability(&mut statement.variable);
+        self.parse_binding(&mut statement.variable, inferred);
+
+        self.scopes.push(vec![]);
+        self.declare(&statement.variable.binding, statement.variable.mutability);
+
+        self.expect(TokenKind::InKw, "`in`");
+
+        statement.iterable = self.parse_expression();
+
+        self.expect(TokenKind::CloseParen, "`)`");
+
+        statement.body = self.parse_loop_body(&statement.label);
+
+        self.scopes.pop();
+
+        return statement;
+    }
+
+    fn parse_do_while_statement(&mut self, label: Option<String>) -> DoWhileStatement {
+        let mut statement = DoWhileStatement::new();
+        statement.label = label;
+
+        //Skip the DO
+
+        self.i+=1;
+
+        statement.body = self.parse_loop_body(&statement.label);
+
+        self.expect(TokenKind::WhileKw, "`while` after the body of a `do` loop");
+
+        self.expect(TokenKind::OpenParen, "`(`");
+
+        statement.condition = self.parse_expression();
+
+        self.expect(TokenKind::CloseParen, "`)`");
+
+        self.expect(TokenKind::Semicolon, "`;`");
+
+        return statement;
+    }
+
+    // Parses the body of a loop, keeping track of which loops `break` and `continue` can reach
+    fn parse_loop_body(&mut self, label: &Option<String>) -> Block {
+        self.loops.push(label.clone());
+
+        let block = self.parse_block();
+
+        self.loops.pop();
+
+        return block;
+    }
+
+    // Parses a `{ ... }` list of statements, including both curlies
+    fn parse_block(&mut self) -> Block {
+        let mut block = Block::new();
+
+        block.open_brace = self.peek(0).location;
+        block.span.start = self.peek(0).span.start;
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        self.scopes.push(vec![]);
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            block.statements.push(self.parse_statement());
+        }
+
+        self.scopes.pop();
+
+        block.close_brace = self.peek(0).location;
+        block.span.end = self.peek(0).span.end;
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return block;
+    }
+
+    // Parses the rest of a `break` or `continue`, returning its label
+    fn parse_loop_jump(&mut self, keyword: &str) -> Option<String> {
+        let mut label = None;
+
+        if self.loops.is_empty() {
+            self.error(format!("`{}` outside of a loop", keyword));
+        }
+
+        //Skip the BREAK or CONTINUE
+
+        self.i+=1;
+
+        if self.match_token(0, TokenKind::Identifier) {
+            let name = self.peek(0).literal.to_string();
+
+            if ! self.loops.is_empty() && ! self.loops.contains(&Some(name.clone())) {
+                self.error(format!("`{} {}` doesn't match any enclosing loop label", keyword, name));
+            }
+
+            label = Some(name);
+            self.i+=1;
+        }
+
+        return label;
+    }
+
+    fn parse_match_statement(&mut self) -> MatchStatement {
+        let mut statement = MatchStatement::new();
+
+        //Skip the MATCH
+
+        self.i+=1;
+
+        self.expect(TokenKind::OpenParen, "`(`");
+
+        statement.value = self.parse_expression();
+
+        self.expect(TokenKind::CloseParen, "`)`");
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            let pattern = self.parse_pattern();
+
+            self.expect(TokenKind::FatArrow, "`=>`");
+
+            // Arms are separated by commas, so an expression body has no `;` of its own
+            let body = if self.match_token(0, TokenKind::OpenCurly) {
+                Statement::Block(self.parse_block())
+            } else if self.is_expression_start(0) {
+                self.parse_expression_statement()
+            } else {
+                self.parse_unterminated_statement()
+            };
+
+            statement.arms.push(MatchArm {pattern, body});
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+        }
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return statement;
+    }
+
+    fn parse_pattern(&mut self) -> Pattern {
+        let pattern = self.parse_single_pattern();
+
+        if ! self.match_token(0, TokenKind::Pipe) {
+            return pattern;
+        }
+
+        let mut alternatives = vec![pattern];
+
+        while self.match_token(0, TokenKind::Pipe) {
+            //Skip the '|'
+            self.i+=1;
+
+            alternatives.push(self.parse_single_pattern());
+        }
+
+        return Pattern::Alternative(alternatives);
+    }
+
+    fn parse_single_pattern(&mut self) -> Pattern {
+        if self.match_token(0, TokenKind::Identifier) {
+            let name = self.peek(0).literal.to_string();
+
+            self.i+=1;
+
+            if name == "_" {
+                return Pattern::Wildcard;
+            }
+            else if self.match_token(0, TokenKind::OpenParen) {
+                let mut variant = EnumVariantPattern {name, fields: vec![]};
+
+                //Skip the '('
+                self.i+=1;
+
+                while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+                    variant.fields.push(self.parse_pattern());
+
+                    if self.match_token(0, TokenKind::Comma) {
+                        self.i+=1;
+                    }
+                    else if ! self.match_token(0, TokenKind::CloseParen) {
+                        break;
+                    }
+                }
+
+                self.expect(TokenKind::CloseParen, "`)`");
+
+                return Pattern::EnumVariant(variant);
+            }
+            else if name.contains('.') {
+                // Paths can't be bound to, so `Color.Red` names a variant without fields
+                return Pattern::EnumVariant(EnumVariantPattern {name, fields: vec![]});
+            }
+
+            return Pattern::Binding(name);
+        }
+
+        let term = self.parse_term();
+
+        if let Term::Null(_) = term {
+            self.error(format!("Expected a pattern but found `{}`", self.peek(0).literal));
+            self.i+=1;
+        }
+
+        return Pattern::Literal(term);
+    }
+
+    fn parse_try_statement(&mut self) -> TryStatement {
+        let mut statement = TryStatement::new();
+        let location = self.peek(0).location;
+
+        //Skip the TRY
+
+        self.i+=1;
+
+        statement.body = self.parse_block();
+
+        while self.match_token(0, TokenKind::CatchKw) {
+            statement.catches.push(self.parse_catch_clause());
+        }
+
+        if self.match_token(0, TokenKind::FinallyKw) {
+            //Skip the FINALLY
+            self.i+=1;
+
+            statement.finally = Some(self.parse_block());
+        }
+
+        if statement.catches.is_empty() && statement.finally.is_none() {
+            self.report(DiagnosticLevel::Error, "`try` needs at least one `catch` or a `finally`".to_string(), location);
+        }
+
+        return statement;
+    }
+
+    fn parse_catch_clause(&mut self) -> CatchClause {
+        let mut parameter = None;
+
+        //Skip the CATCH
+
+        self.i+=1;
+
+        if self.match_token(0, TokenKind::OpenParen) {
+            //Skip the '('
+            self.i+=1;
+
+            parameter = Some(self.parse_variable_definition());
+
+            self.expect(TokenKind::CloseParen, "`)`");
+        }
+        else if ! self.match_token(0, TokenKind::OpenCurly) {
+            self.error(format!("Expected `(` or `{{` after `catch` but found `{}`", self.peek(0).literal));
+            return CatchClause {parameter, body: Block::new()};
+        }
+
+        self.scopes.push(vec![]);
+        if let Some(parameter) = &parameter {
+            self.declare(&parameter.binding, parameter.mutability);
+        }
+
+        let body = self.parse_block();
+
+        self.scopes.pop();
+
+        return CatchClause {parameter, body};
+    }
+
+    fn parse_return_statement(&mut self) -> ReturnStatement {
+        let mut statement = ReturnStatement::new();
+
+        //Skip the RETURN
+
+        self.i+=1;
+
+        if ! self.match_token(0, TokenKind::Semicolon) {
+            statement.value = self.parse_expression();
+        }
+
+        return statement;
+    }
+
+    fn parse_variable_redefinition(&mut self) -> VariableRedefinition {
+        let mut redefinition = VariableRedefinition::new();
+
+        redefinition.name = self.peek(0).literal.to_string();
+
+        match self.lookup(&redefinition.name) {
+            Some(mutability) => self.check_reassignment(&redefinition.name, mutability, self.peek(0).location),
+            None => self.unresolved.push((redefinition.name.clone(), self.peek(0).location)),
+        }
+
+        self.i+=1;
+
+        redefinition.op = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        redefinition.value = self.parse_expression();
+
+        return redefinition;
+    }
+
+    fn parse_class_definition(&mut self) -> ClassDefinition {
+        let mut class = ClassDefinition::new();
+
+        class.visibility = self.parse_visibility();
+
+        self.i+=1;
+
+        class.name = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        class.type_parameters = self.parse_type_parameters();
+
+        class.bases = self.parse_base_list();
+
+        // SKip open curly
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        let outer = self.class_scope.replace(self.scopes.len());
+        self.scopes.push(vec![]);
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            let start = self.i;
+            let location = self.peek(0).location;
+            let definition = self.parse_definition();
+
+            if self.i == start {
+                self.report(DiagnosticLevel::Error, format!("Expected a class member but found `{}`", self.peek(0).literal), location);
+                self.i+=1;
+                continue;
+            }
+
+            self.declare_member(&definition);
+            class.definitions.push(definition);
+        }
+
+        self.close_member_scope();
+        self.class_scope = outer;
+
+        // `this.x` that isn't a member of this class can't be a member of anything further out
+        self.unresolved.retain(|(name, _)| ! name.starts_with("this."));
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return class;
+    }
+
+    fn parse_interface_definition(&mut self) -> InterfaceDefinition {
+        let mut interface = InterfaceDefinition::new();
+
+        interface.visibility = self.parse_visibility();
+
+        // Skip the INTERFACE
+
+        self.i+=1;
+
+        interface.name = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        interface.type_parameters = self.parse_type_parameters();
+
+        interface.bases = self.parse_base_list();
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            // Everything in an interface is public and belongs to instances
+            let modifiers = self.modifiers_length(0);
+            if modifiers > 0 {
+                self.error("Interface members can't have modifiers".to_string());
+                self.i+=modifiers as usize;
+            }
+
+            interface.methods.push(self.parse_method_signature());
+
+            if self.match_token(0, TokenKind::OpenCurly) {
+                self.error(format!("Interface method `{}` can't have a body", interface.methods.last().unwrap().name));
+                self.parse_block();
+            }
+            else {
+                self.expect(TokenKind::Semicolon, "`;`");
+            }
+        }
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return interface;
+    }
+
+    fn parse_struct_definition(&mut self) -> StructDefinition {
+        let mut structure = StructDefinition::new();
+
+        structure.visibility = self.parse_visibility();
+
+        // Skip the STRUCT
+
+        self.i+=1;
+
+        structure.name = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        structure.type_parameters = self.parse_type_parameters();
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            let start = self.i;
+            let location = self.peek(0).location;
+
+            match self.parse_definition() {
+                Definition::VariableDefinition(field) => structure.fields.push(field),
+                _ => {
+                    self.report(DiagnosticLevel::Error, format!("Struct `{}` can only contain fields", structure.name), location);
+
+                    if self.i == start {
+                        self.i+=1;
+                    }
+                }
+            }
+        }
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return structure;
+    }
+
+    fn parse_enum_definition(&mut self) -> EnumDefinition {
+        let mut enumeration = EnumDefinition::new();
+
+        enumeration.visibility = self.parse_visibility();
+
+        // Skip the ENUM
+
+        self.i+=1;
+
+        enumeration.name = self.peek(0).literal.to_string();
+
+        self.i+=1;
+
+        enumeration.type_parameters = self.parse_type_parameters();
+
+        self.expect(TokenKind::OpenCurly, "`{`");
+
+        while ! self.match_token(0, TokenKind::CloseCurly) && self.i < self.tokens.len() {
+            let mut variant = EnumVariant::new();
+
+            if ! self.match_token(0, TokenKind::Identifier) {
+                self.error(format!("Expected an enum variant but found `{}`", self.peek(0).literal));
+                self.i+=1;
+                continue;
+            }
+
+            variant.name = self.peek(0).literal.to_string();
+
+            self.i+=1;
+
+            // Payload variants, eg. `Circle(float r)`
+            if self.match_token(0, TokenKind::OpenParen) {
+                // Skip the '('
+
+                self.i+=1;
+
+                while ! self.match_token(0, TokenKind::CloseParen) && self.i < self.tokens.len() {
+                    variant.fields.push(self.parse_variable_definition());
+
+                    if self.match_token(0, TokenKind::Comma) {
+                        self.i+=1;
+                    }
+                }
+
+                // Skip the ')'
+
+                self.i+=1;
+            }
+
+            if self.match_token(0, TokenKind::Equals) {
+                if ! variant.fields.is_empty() {
+                    self.error(format!("Enum variant `{}` can't have both fields and a value", variant.name));
+                }
+
+                //Skip Equals
+
+                self.i+=1;
+
+                variant.value = self.parse_expression();
+            }
+
+            enumeration.variants.push(variant);
+
+            if self.match_token(0, TokenKind::Comma) {
+                self.i+=1;
+            }
+            else if ! self.match_token(0, TokenKind::CloseCurly) {
+                self.error(format!("Expected `,` or `}}` but found `{}`", self.peek(0).literal));
+                self.i+=1;
+            }
+        }
+
+        self.expect(TokenKind::CloseCurly, "`}`");
+
+        return enumeration;
+    }
+
+    // The optional `: Base, IOther` after a class or interface name
+    fn parse_base_list(&mut self) -> Vec<TypeExpr> {
+        let mut bases = vec![];
+
+        if ! self.match_token(0, TokenKind::Colon) {
+            return bases;
+        }
+
+        // Skip the ':'
+
+        self.i+=1;
+
+        loop {
+            bases.push(self.parse_type());
+
+            if ! self.match_token(0, TokenKind::Comma) {
+                break;
+            }
+
+            self.i+=1;
+        }
+
+        return bases;
+    }
+
+    // Records the names bound by a local definition in the innermost scope
+    fn declare(&mut self, binding: &Binding, mutability: Mutability) {
+        match binding {
+            Binding::Name(name) => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.push((name.clone(), mutability));
+                }
+            }
+            Binding::Tuple(elements) => {
+                for element in elements {
+                    self.declare(&element.binding, element.mutability);
+                }
+            }
+        }
+    }
+
+    // Fields and constants can be used before they're defined, unlike locals
+    fn declare_member(&mut self, definition: &Definition) {
+        if let Definition::VariableDefinition(variable) = definition {
+            self.declare(&variable.binding, variable.mutability);
+        }
+    }
+
+    // Pops a namespace or class scope, reporting earlier reassignments of the `let` and `const` members it defined
+    fn close_member_scope(&mut self) {
+        let members = self.scopes.pop().unwrap_or_default();
+
+        let (resolved, unresolved): (Vec<_>, Vec<_>) = std::mem::take(&mut self.unresolved).into_iter()
+            .partition(|(name, _)| members.iter().any(|(member, _)| member == Self::member_name(name)));
+        self.unresolved = unresolved;
+
+        for (name, location) in resolved {
+            let (_, mutability) = members.iter().rev().find(|(member, _)| member == Self::member_name(&name)).unwrap();
+            self.check_reassignment(&name, *mutability, location);
+        }
+    }
+
+    // `this.x` names the member `x`
+    fn member_name(name: &str) -> &str {
+        return name.strip_prefix("this.").unwrap_or(name);
+    }
+
+    // Reports assigning to or incrementing a `let` or `const` variable, or a member through `this`
+    fn check_target(&mut self, target: &Expression, location: SourceLoc) {
+        match target {
+            Expression::Term(Term::VariableReference(variable)) => match self.lookup(&variable.value) {
+                Some(mutability) => self.check_reassignment(&variable.value, mutability, location),
+                None => self.unresolved.push((variable.value.clone(), location)),
+            },
+            // A local with the same name doesn't hide the member here
+            Expression::MemberAccess(access) if matches!(*access.object, Expression::This(_)) => {
+                let Some(scope) = self.class_scope else {
+                    return;
+                };
+
+                let name = format!("this.{}", access.member);
+                match self.scopes[scope].iter().rev().find(|(member, _)| *member == access.member) {
+                    Some((_, mutability)) => self.check_reassignment(&name, *mutability, location),
+                    None => self.unresolved.push((name, location)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_reassignment(&mut self, name: &str, mutability: Mutability, location: SourceLoc) {
+        match mutability {
+            Mutability::Immutable => self.report(DiagnosticLevel::Error, format!("Cannot reassign `{}`, it was defined with `let`", name), location),
+            Mutability::Constant => self.report(DiagnosticLevel::Error, format!("Cannot reassign the constant `{}`", name), location),
+            Mutability::Mutable => {}
+        }
+    }
+
+    // Finds how the closest variable called `name` was defined, if it's a local or a member seen so far
+    fn lookup(&self, name: &str) -> Option<Mutability> {
+        for scope in self.scopes.iter().rev() {
+            if let Some((_, mutability)) = scope.iter().rev().find(|(local, _)| local == name) {
+                return Some(*mutability);
+            }
+        }
+
+        return None;
+    }
+
+    // Skips the expected token, or reports it as missing
+    fn expect(&mut self, kind: TokenKind, expected: &str) {
+        if self.match_token(0, kind) {
+            self.i+=1;
+        }
+        else if let (true, Some(last)) = (self.i >= self.tokens.len(), self.tokens.last()) {
+            // Point at the last token, there's nothing after it to point at
+            let location = last.location.clone();
+            self.report(DiagnosticLevel::Error, format!("Expected {} but reached the end of the file", expected), location);
+        }
+        else {
+            self.error(format!("Expected {} but found `{}`", expected, self.peek(0).literal));
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        let location = self.peek(0).location;
+        self.report(DiagnosticLevel::Error, message, location);
+    }
+
+    fn report(&mut self, level: DiagnosticLevel, message: String, location: SourceLoc) {
+        self.diagnostics.push(Diagnostic {level, message, location});
+    }
+
+    // Number of tokens taken up by the type at `offset`, or 0 if there isn't one
+    fn type_length(&self, offset: isize) -> isize {
+        let mut closed = false;
+        let length = self.scan_type(offset, &mut closed);
+
+        // A `>>` with nothing left to close isn't part of a type
+        if closed {
+            return 0;
+        }
+
+        return length;
+    }
+
+    // Like `type_length`, sets `closed` when the type ended on a `>>` that also closes the enclosing argument list
+    fn scan_type(&self, offset: isize, closed: &mut bool) -> isize {
+        let mut length: isize;
+
+        if self.match_token(offset, TokenKind::Identifier) {
+            length = 1;
+
+            if self.match_token(offset + length, TokenKind::LessThan) {
+                let arguments = self.scan_type_arguments(offset + length, closed);
+                if arguments == 0 {
+                    return 0;
+                }
+                length += arguments;
+
+                if *closed {
+                    return length;
+                }
+            }
+        }
+        else if self.match_token(offset, TokenKind::OpenParen) {
+            // Tuple and function types, eg. `(int, string)` or `(int) -> bool`
+            length = 1;
+
+            while ! self.match_token(offset + length, TokenKind::CloseParen) {
+                let element = self.type_length(offset + length);
+                if element == 0 {
+                    return 0;
+                }
+                length += element;
+
+                if self.match_token(offset + length, TokenKind::Comma) {
+                    length += 1;
+                }
+                else if ! self.match_token(offset + length, TokenKind::CloseParen) {
+                    return 0;
+                }
+            }
+
+            // Count the ')', `()` has no elements before it
+            length += 1;
+
+            if self.match_token(offset + length, TokenKind::Arrow) {
+                let return_type = self.scan_type(offset + length + 1, closed);
+                if return_type == 0 {
+                    return 0;
+                }
+
+                return length + 1 + return_type;
+            }
+        }
+        else {
+            return 0;
+        }
+
+        loop {
+            if self.match_token(offset + length, TokenKind::OpenBracket) && self.match_token(offset + length + 1, TokenKind::CloseBarcket) {
+                length += 2;
+            }
+            else if self.match_token(offset + length, TokenKind::Question) {
+                length += 1;
+            }
+            else {
+                break;
+            }
+        }
+
+        return length;
+    }
+
+    // Number of tokens taken up by the `<...>` type argument list at `offset`, sets `closed` like `scan_type`
+    fn scan_type_arguments(&self, offset: isize, closed: &mut bool) -> isize {
+        let mut length = 1;
+
+        loop {
+            let mut inner_closed = false;
+            let argument = self.scan_type(offset + length, &mut inner_closed);
+            if argument == 0 {
+                return 0;
+            }
+            length += argument;
+
+            if inner_closed {
+                return length;
+            }
+
+            if self.match_token(offset + length, TokenKind::Comma) {
+                length += 1;
+            }
+            else if self.match_token(offset + length, TokenKind::GreaterThan) {
+                return length + 1;
+            }
+            else if self.match_token(offset + length, TokenKind::ShiftRight) {
+                *closed = true;
+                return length + 1;
+            }
+            else {
+                return 0;
+            }
+        }
+    }
+
+    // Number of tokens taken up by the type arguments of a generic call such as `max<int>(a, b)`, or 0 if there aren't any.
+    // Like C#, `a < b, c > (d)` is read as a generic call whenever the tokens fit one
+    fn generic_call_length(&self, offset: isize) -> isize {
+        if ! self.match_token(offset, TokenKind::LessThan) {
+            return 0;
+        }
+
+        let mut closed = false;
+        let length = self.scan_type_arguments(offset, &mut closed);
+        if length == 0 || closed || ! self.match_token(offset + length, TokenKind::OpenParen) {
+            return 0;
+        }
+
+        return length;
+    }
+
+    // Number of tokens taken up by the `<T, U : Comparable>` type parameter list at `offset`, or 0 if there isn't one
+    fn type_parameters_length(&self, offset: isize) -> isize {
+        if ! self.match_token(offset, TokenKind::LessThan) {
+            return 0;
+        }
+
+        let mut length = 1;
+
+        loop {
+            if ! self.match_token(offset + length, TokenKind::Identifier) {
+                return 0;
+            }
+            length += 1;
+
+            if self.match_token(offset + length, TokenKind::Colon) {
+                length += 1;
+
+                loop {
+                    let mut closed = false;
+                    let constraint = self.scan_type(offset + length, &mut closed);
+                    if constraint == 0 {
+                        return 0;
+                    }
+                    length += constraint;
+
+                    if closed {
+                        return length;
+                    }
+
+                    if ! self.match_token(offset + length, TokenKind::Add) {
+                        break;
+                    }
+                    length += 1;
+                }
+            }
+
+            if self.match_token(offset + length, TokenKind::Comma) {
+                length += 1;
+            }
+            else if self.match_token(offset + length, TokenKind::GreaterThan) {
+                return length + 1;
+            }
+            else {
+                return 0;
+            }
+        }
+    }
+
+    // Number of tokens taken up by a `Type name` or `(Type a, Type b)` declaration at `offset`, or 0 if there isn't one
+    fn declaration_length(&self, offset: isize) -> isize {
+        let length = self.type_length(offset);
+        if length > 0 && self.match_token(offset + length, TokenKind::Identifier) {
+            return length + 1;
+        }
+
+        return self.tuple_binding_length(offset);
+    }
+
+    fn tuple_binding_length(&self, offset: isize) -> isize {
+        if ! self.match_token(offset, TokenKind::OpenParen) {
+            return 0;
+        }
+
+        let mut length = 1;
+
+        loop {
+            let element = self.declaration_length(offset + length);
+            if element == 0 {
+                return 0;
+            }
+            length += element;
+
+            if self.match_token(offset + length, TokenKind::Comma) {
+                length += 1;
+            }
+            else if self.match_token(offset + length, TokenKind::CloseParen) {
+                return length + 1;
+            }
+            else {
+                return 0;
+            }
+        }
+    }
+
+    fn is_tuple_binding(&self, offset: isize) -> bool {
+        return self.tuple_binding_length(offset) > 0;
+    }
+
+    // `Name { }` or `Name { field: ...`, braces after a plain name aren't otherwise valid in an expression
+    fn is_struct_literal(&self, offset: isize) -> bool {
+        if ! self.match_token(offset, TokenKind::Identifier) || ! self.match_token(offset + 1, TokenKind::OpenCurly) {
+            return false;
+        }
+
+        return self.match_token(offset + 2, TokenKind::CloseCurly) || (self.match_token(offset + 2, TokenKind::Identifier) && self.match_token(offset + 3, TokenKind::Colon));
+    }
+
+    // Number of modifier keywords, eg. `pub static`, at `offset`
+    fn modifiers_length(&self, offset: isize) -> isize {
+        let mut length = 0;
+        while matches!(self.peek(offset + length).kind, TokenKind::PubKw | TokenKind::PrivateKw | TokenKind::ProtectedKw | TokenKind::InternalKw | TokenKind::StaticKw) {
+            length += 1;
+        }
+
+        return length;
+    }
+
+    // `Type name(` or `Type name<T>(`, the start of a function written without `fn`
+    fn is_function_declaration(&self, offset: isize) -> bool {
+        if ! self.is_declaration(offset) {
+            return false;
+        }
+
+        let name = offset + self.type_length(offset);
+        return self.match_token(name + 1 + self.type_parameters_length(name + 1), TokenKind::OpenParen);
+    }
+
+    // Whether a declaration starts at `offset`
+    fn is_declaration(&self, offset: isize) -> bool {
+        return self.declaration_length(offset) > 0;
+    }
+
+    // `=` or any compound assignment like `+=`
+    fn is_assignment_operator(&self, offset: isize) -> bool {
+        return self.match_token(offset, TokenKind::Equals) || Op::from_assignment_token(&self.peek(offset).kind).is_some();
+    }
+
+    // Number of tokens taken up by a for-each variable such as `int x`, `let x` or `var (int a, int b)`, or 0 if there isn't one
+    fn loop_variable_length(&self, offset: isize) -> isize {
+        if ! self.is_mutability_keyword(offset) {
+            return self.declaration_length(offset);
+        }
+
+        let length = self.declaration_length(offset + 1);
+        if length > 0 {
+            return length + 1;
+        }
+        else if self.match_token(offset + 1, TokenKind::Identifier) {
+            return 2;
+        }
+
+        return 0;
+    }
+
+    // `name = ...`, members of `this` are assigned through `Statement::Assignment` instead
+    fn is_variable_redefinition(&self, offset: isize) -> bool {
+        return self.match_token(offset, TokenKind::Identifier) && ! self.peek(offset).literal.starts_with("this.") && self.is_assignment_operator(offset + 1);
+    }
+
+    fn is_mutability_keyword(&self, offset: isize) -> bool {
+        return matches!(self.peek(offset).kind, TokenKind::LetKw | TokenKind::VarKw | TokenKind::ConstKw);
+    }
+
+    fn is_range_operator(&self, offset: isize) -> bool {
+        return self.match_token(offset, TokenKind::DotDot) || self.match_token(offset, TokenKind::DotDotEquals);
+    }
+
+    // `-` directly followed by a numeric literal, eg. `-128i8`
+    fn is_negative_literal(&self, offset: isize) -> bool {
+        return self.match_token(offset, TokenKind::Subtract) && (self.peek(offset + 1).is_int_literal() || self.match_token(offset + 1, TokenKind::FloatLit));
+    }
+
+    // Whether the token at `offset` can begin an expression
+    fn is_expression_start(&self, offset: isize) -> bool {
+        let token = self.peek(offset);
+        return token.is_int_literal() || matches!(
+            token.kind,
+            TokenKind::Identifier | TokenKind::FloatLit | TokenKind::StringLit | TokenKind::TrueKw
+                | TokenKind::FalseKw | TokenKind::NullKw | TokenKind::OpenParen | TokenKind::Subtract
+                | TokenKind::Bang | TokenKind::Tilde | TokenKind::Increment | TokenKind::Decrement | TokenKind::NewKw
+                | TokenKind::ThisKw
+        );
+    }
+
+    fn match_token(&self, offset: isize, t: TokenKind) -> bool {
+        return self.peek(offset).kind == t;
+    }
+
+    fn peek(&self, offset: isize) -> Token<'a> {
+        let index = self.i as isize + offset;
+        if index >= 0 && index < self.tokens.len() as isize {
+            self.tokens[index as usize].clone()
+        } else {
+            Token {kind: TokenKind::Whitespace, location: SourceLoc {line: 0, start: 0, end: 0}, span: Span::default(), literal: ""}
+        }
     }
 }
\ No newline at end of file